@@ -0,0 +1,159 @@
+//! Derive macros for [omniswap](https://docs.rs/omniswap).
+//!
+//! Please use this crate through the `derive` feature of `omniswap`
+//! rather than depending on it directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DataEnum, DataStruct, DeriveInput, Error,
+    Field, Fields, Meta,
+};
+
+/// Derives `omniswap::Sentinel`.
+///
+/// See the documentation of `omniswap::Sentinel` for details.
+#[proc_macro_derive(Sentinel, attributes(sentinel))]
+pub fn derive_sentinel(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_sentinel(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_sentinel(mut input: DeriveInput) -> Result<TokenStream2, Error> {
+    let body = match &input.data {
+        Data::Struct(data) => {
+            for param in input.generics.type_params_mut() {
+                param.bounds.push(parse_quote!(::omniswap::Sentinel));
+            }
+            struct_body(data)?
+        }
+        Data::Enum(data) => enum_body(&input.ident, data)?,
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span,
+                "`#[derive(Sentinel)]` does not support unions",
+            ));
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::omniswap::Sentinel for #name #ty_generics #where_clause {
+            fn sentinel() -> Self {
+                #body
+            }
+        }
+    })
+}
+
+fn struct_body(data: &DataStruct) -> Result<TokenStream2, Error> {
+    match &data.fields {
+        Fields::Named(fields) => {
+            let inits = fields
+                .named
+                .iter()
+                .map(|field| {
+                    let name = &field.ident;
+                    let value = field_sentinel(field)?;
+                    Ok(quote! { #name: #value })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            Ok(quote! { Self { #(#inits,)* } })
+        }
+        Fields::Unnamed(fields) => {
+            let inits = fields
+                .unnamed
+                .iter()
+                .map(field_sentinel)
+                .collect::<Result<Vec<_>, Error>>()?;
+            Ok(quote! { Self(#(#inits,)*) })
+        }
+        Fields::Unit => Ok(quote! { Self }),
+    }
+}
+
+/// Builds the sentinel expression for a field.
+///
+/// The expression is spanned to the field type so that a missing
+/// `Sentinel` implementation is reported there.
+fn field_sentinel(field: &Field) -> Result<TokenStream2, Error> {
+    let ty = &field.ty;
+    let mut use_default = false;
+    for attr in sentinel_attrs(&field.attrs) {
+        match &attr.meta {
+            Meta::List(list) => {
+                list.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("default") {
+                        use_default = true;
+                        Ok(())
+                    } else {
+                        Err(meta.error("expected `default`"))
+                    }
+                })?;
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "expected `#[sentinel(default)]` on a field",
+                ));
+            }
+        }
+    }
+    if use_default {
+        Ok(quote_spanned! {ty.span()=> <#ty as ::core::default::Default>::default() })
+    } else {
+        Ok(quote_spanned! {ty.span()=> <#ty as ::omniswap::Sentinel>::sentinel() })
+    }
+}
+
+fn enum_body(name: &syn::Ident, data: &DataEnum) -> Result<TokenStream2, Error> {
+    let mut found = None;
+    for variant in &data.variants {
+        let mut attrs = sentinel_attrs(&variant.attrs);
+        let Some(attr) = attrs.next() else {
+            continue;
+        };
+        if !matches!(attr.meta, Meta::Path(_)) {
+            return Err(Error::new_spanned(
+                attr,
+                "expected `#[sentinel]` without arguments on a variant",
+            ));
+        }
+        if let Some(attr) = attrs.next() {
+            return Err(Error::new_spanned(
+                attr,
+                "duplicate `#[sentinel]` attribute",
+            ));
+        }
+        if found.is_some() {
+            return Err(Error::new_spanned(
+                attr,
+                "only one variant can be marked `#[sentinel]`",
+            ));
+        }
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                &variant.fields,
+                "the `#[sentinel]` variant must be a unit variant",
+            ));
+        }
+        found = Some(&variant.ident);
+    }
+
+    let Some(variant) = found else {
+        return Err(Error::new(
+            name.span(),
+            "`#[derive(Sentinel)]` on an enum requires a unit variant marked `#[sentinel]`",
+        ));
+    };
+    Ok(quote! { Self::#variant })
+}
+
+fn sentinel_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("sentinel"))
+}
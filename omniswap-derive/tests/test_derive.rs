@@ -0,0 +1,82 @@
+use std::marker::PhantomData;
+
+use omniswap::{swap, take, Sentinel};
+
+#[derive(Debug, PartialEq, Eq, Sentinel)]
+struct Named {
+    id: u32,
+    next: Option<Box<Named>>,
+    #[sentinel(default)]
+    log: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Sentinel)]
+struct Tuple(u8, [char; 2]);
+
+#[derive(Debug, PartialEq, Eq, Sentinel)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Eq, Sentinel)]
+struct Generic<T> {
+    value: T,
+    marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, PartialEq, Eq, Sentinel)]
+enum State {
+    Running(u32),
+    #[sentinel]
+    Idle,
+    Done {
+        code: i32,
+    },
+}
+
+#[test]
+fn test_derive_struct() {
+    assert_eq!(
+        Named::sentinel(),
+        Named {
+            id: 0,
+            next: None,
+            log: vec![],
+        }
+    );
+    assert_eq!(Tuple::sentinel(), Tuple(0, ['\0', '\0']));
+    assert_eq!(Unit::sentinel(), Unit);
+    assert_eq!(
+        Generic::<i64>::sentinel(),
+        Generic {
+            value: 0,
+            marker: PhantomData,
+        }
+    );
+}
+
+#[test]
+fn test_derive_enum() {
+    assert_eq!(State::sentinel(), State::Idle);
+}
+
+#[test]
+fn test_derive_take() {
+    let mut place = State::Done { code: 1 };
+    assert_eq!(take!(&mut place), State::Done { code: 1 });
+    assert_eq!(place, State::Idle);
+}
+
+#[test]
+fn test_derive_swap() {
+    let mut a = [
+        [State::Running(1), State::Idle],
+        [State::Idle, State::Done { code: 2 }],
+    ];
+    swap!(&mut a[0][0], &mut a[1][1]);
+    assert_eq!(
+        a,
+        [
+            [State::Done { code: 2 }, State::Idle],
+            [State::Idle, State::Running(1)],
+        ]
+    );
+}
@@ -0,0 +1,11 @@
+use omniswap::Sentinel;
+
+#[derive(Sentinel)]
+enum State {
+    #[sentinel]
+    Empty,
+    #[sentinel]
+    Idle,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use omniswap::Sentinel;
+
+#[derive(Sentinel)]
+enum State {
+    Running(u32),
+    Idle,
+}
+
+fn main() {}
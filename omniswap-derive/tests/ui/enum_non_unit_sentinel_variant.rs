@@ -0,0 +1,10 @@
+use omniswap::Sentinel;
+
+#[derive(Sentinel)]
+enum State {
+    #[sentinel]
+    Running(u32),
+    Idle,
+}
+
+fn main() {}
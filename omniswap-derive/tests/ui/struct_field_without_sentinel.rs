@@ -0,0 +1,11 @@
+use omniswap::Sentinel;
+
+struct Resource;
+
+#[derive(Sentinel)]
+struct Slot {
+    id: u32,
+    resource: Resource,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use omniswap::Sentinel;
+
+#[derive(Sentinel)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+fn main() {}
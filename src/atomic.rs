@@ -0,0 +1,123 @@
+use core::convert::Infallible;
+use core::sync::atomic::Ordering;
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::{AtomicBool, AtomicI8, AtomicU8};
+#[cfg(target_has_atomic = "16")]
+use core::sync::atomic::{AtomicI16, AtomicU16};
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::{AtomicI32, AtomicU32};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicI64, AtomicU64};
+#[cfg(target_has_atomic = "ptr")]
+use core::sync::atomic::{AtomicIsize, AtomicPtr, AtomicUsize};
+
+use crate::{CopyOut, Replace, Strategy, TakeHelper, TryError, TryReplace};
+
+/// A variant of [`Replace`] with an explicit memory ordering.
+///
+/// It is implemented for references to the types in [`core::sync::atomic`],
+/// and used in the ordered forms of the macros, such as `swap!(&a, &b; Ordering::AcqRel)`.
+///
+/// The plain [`Replace`] implementations of these types use [`Ordering::SeqCst`].
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use omniswap::ReplaceOrdered;
+/// let x = AtomicUsize::new(42);
+/// assert_eq!(ReplaceOrdered::take_ordered(&x, Ordering::Acquire), 42);
+/// assert_eq!(ReplaceOrdered::replace_ordered(&x, 84, Ordering::AcqRel), 42);
+/// assert_eq!(x.into_inner(), 84);
+/// ```
+pub trait ReplaceOrdered<T>: Replace<T> {
+    /// Copies out the value, as in [`take!`](crate::take!).
+    ///
+    /// Orderings only valid for stores are weakened:
+    /// [`Release`](Ordering::Release) to [`Relaxed`](Ordering::Relaxed)
+    /// and [`AcqRel`](Ordering::AcqRel) to [`Acquire`](Ordering::Acquire).
+    fn take_ordered(self, order: Ordering) -> T;
+    fn replace_ordered(self, value: T, order: Ordering) -> T;
+}
+
+/// Converts an ordering for a read-modify-write operation into one valid for a load.
+fn load_ordering(order: Ordering) -> Ordering {
+    match order {
+        Ordering::Release => Ordering::Relaxed,
+        Ordering::AcqRel => Ordering::Acquire,
+        order => order,
+    }
+}
+
+macro_rules! impl_atomic {
+    ($($width:literal: [$($param:ident),*] $atomic:ty => $value:ty;)*) => {
+        $(
+            #[cfg(target_has_atomic = $width)]
+            impl<'a $(, $param)*> Replace<$value> for &'a $atomic {
+                fn replace(self, value: $value) -> $value {
+                    self.swap(value, Ordering::SeqCst)
+                }
+            }
+
+            #[cfg(target_has_atomic = $width)]
+            impl<'a $(, $param)*> TryReplace<$value> for &'a $atomic {
+                type Error = Infallible;
+                fn try_replace(self, value: $value) -> Result<$value, (Self::Error, $value)> {
+                    Ok(self.replace(value))
+                }
+            }
+
+            #[cfg(target_has_atomic = $width)]
+            impl<'a $(, $param)*> ReplaceOrdered<$value> for &'a $atomic {
+                fn take_ordered(self, order: Ordering) -> $value {
+                    self.load(load_ordering(order))
+                }
+
+                fn replace_ordered(self, value: $value, order: Ordering) -> $value {
+                    self.swap(value, order)
+                }
+            }
+
+            #[cfg(target_has_atomic = $width)]
+            impl<'a $(, $param)*> CopyOut<$value> for &'a $atomic {
+                fn copy_out(self) -> $value {
+                    self.load(Ordering::SeqCst)
+                }
+            }
+
+            #[cfg(target_has_atomic = $width)]
+            impl<'a $(, $param)*> TakeHelper<&'a $atomic> {
+                pub fn take(&mut self) -> $value {
+                    self.0 .0 .0 .0 .0.load(Ordering::SeqCst)
+                }
+
+                pub fn try_take(&mut self) -> Result<$value, TryError> {
+                    Ok(self.take())
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Copy
+                }
+
+                pub fn take_no_clone(&mut self) -> $value {
+                    self.take()
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic! {
+    "8": [] AtomicBool => bool;
+    "8": [] AtomicI8 => i8;
+    "8": [] AtomicU8 => u8;
+    "16": [] AtomicI16 => i16;
+    "16": [] AtomicU16 => u16;
+    "32": [] AtomicI32 => i32;
+    "32": [] AtomicU32 => u32;
+    "64": [] AtomicI64 => i64;
+    "64": [] AtomicU64 => u64;
+    "ptr": [] AtomicIsize => isize;
+    "ptr": [] AtomicUsize => usize;
+    "ptr": [T] AtomicPtr<T> => *mut T;
+}
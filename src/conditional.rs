@@ -0,0 +1,124 @@
+use core::hint::black_box;
+use core::ops::{BitAnd, BitXor};
+
+/// Swaps values of two references if the condition holds.
+///
+/// It is a shorthand for `if cond { swap!(x, y) }`,
+/// and accepts the same places as [`swap!`](crate::swap!).
+///
+/// ## Usage
+///
+/// ```rust
+/// // A sorting network for three elements.
+/// let mut a = [3, 1, 2];
+/// omniswap::swap_if!(a[0] > a[1], mut a[0], mut a[1]);
+/// omniswap::swap_if!(a[1] > a[2], mut a[1], mut a[2]);
+/// omniswap::swap_if!(a[0] > a[1], mut a[0], mut a[1]);
+/// assert_eq!(a, [1, 2, 3]);
+/// ```
+///
+/// With an explicit sentinel as in `swap!(with ...)`, it returns the leftover sentinel,
+/// or the sentinel unchanged if the condition does not hold.
+///
+/// ```rust
+/// let mut x = String::from("x");
+/// let mut y = String::from("y");
+/// let s = omniswap::swap_if!(false, with String::from("-"); &mut x, &mut y);
+/// assert_eq!((x.as_str(), y.as_str(), s.as_str()), ("x", "y", "-"));
+/// ```
+///
+/// ## Evaluation order
+///
+/// It evaluates the condition first.
+/// If it holds, the sentinel and the places are evaluated in the same order as [`swap!`](crate::swap!).
+/// Otherwise, only the sentinel is evaluated, if any.
+#[macro_export]
+macro_rules! swap_if {
+    ($cond: expr, with $sentinel: expr; $($args: tt)*) => {
+        if $cond {
+            $crate::swap!(with $sentinel; $($args)*)
+        } else {
+            $sentinel
+        }
+    };
+    ($cond: expr, $($args: tt)*) => {
+        if $cond {
+            $crate::swap!($($args)*)
+        }
+    };
+}
+
+/// Integer types supported by [`cswap!`](crate::cswap!).
+pub trait CSwap: Copy + BitAnd<Output = Self> + BitXor<Output = Self> {
+    /// Returns all ones if `choice` is `true`, and zero otherwise, without branching.
+    fn mask(choice: bool) -> Self;
+
+    /// Returns `(y, x)` if `choice` is `true`, and `(x, y)` otherwise, without branching.
+    fn cswap(choice: bool, x: Self, y: Self) -> (Self, Self) {
+        let t = Self::mask(choice) & (x ^ y);
+        (x ^ t, y ^ t)
+    }
+}
+
+macro_rules! impl_cswap {
+    ($($t:ty),*) => {
+        $(
+            impl CSwap for $t {
+                fn mask(choice: bool) -> Self {
+                    (black_box(choice) as $t).wrapping_neg()
+                }
+            }
+        )*
+    };
+}
+
+impl_cswap!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Swaps two integers if the condition holds, without branching on the condition.
+///
+/// The values are always read and written back, and the swap is done by masking:
+/// `t = mask(cond) & (x ^ y)`, then `x ^= t` and `y ^= t`.
+/// This is meant for sorting networks and constant-time code,
+/// although the compiler does not guarantee that no branch is introduced.
+///
+/// The value type must implement [`CSwap`], that is, be a primitive integer type.
+/// The places may be `&mut T`, [`&Cell<T>`](std::cell::Cell) or [`&RefCell<T>`](std::cell::RefCell),
+/// and the place syntax is accepted as in [`swap!`](crate::swap!).
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut a = [5u32, 3];
+/// let secret = a[0] > a[1];
+/// omniswap::cswap!(secret, mut a[0], mut a[1]);
+/// assert_eq!(a, [3, 5]);
+/// ```
+///
+/// ## Evaluation order
+///
+/// It evaluates the condition first, and then the places in the order of appearance, twice.
+#[macro_export]
+macro_rules! cswap {
+    ($cond: expr, $($args: tt)*) => {
+        {
+            let choice: bool = $cond;
+            $crate::__place_args!(__cswap_impl (cswap choice) cswap [] $($args)*)
+        }
+    };
+}
+
+/// Internal macro used in [`cswap!`].
+///
+/// Please use [`cswap!`] instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cswap_impl {
+    (@start (cswap $choice: ident) cswap [$x: expr, $y: expr]) => {{
+        let (x, y) = $crate::CSwap::cswap($choice, $crate::take_copy($x), $crate::take_copy($y));
+        let _ = $crate::Replace::replace($x, x);
+        let _ = $crate::Replace::replace($y, y);
+    }};
+    (@start $mode: tt cswap [$($out: expr),*]) => {
+        ::core::compile_error!("expected exactly two places")
+    };
+}
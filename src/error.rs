@@ -0,0 +1,58 @@
+use core::cell::{BorrowError, BorrowMutError};
+use core::convert::Infallible;
+use core::fmt;
+
+/// An error returned by [`try_take!`](crate::try_take!), [`try_swap!`](crate::try_swap!)
+/// and [`try_rotate!`](crate::try_rotate!) when a place is unavailable.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum TryError {
+    /// The value could not be read because the [`RefCell`](core::cell::RefCell) was mutably borrowed.
+    Borrow(BorrowError),
+    /// The value could not be written because the [`RefCell`](core::cell::RefCell) was borrowed.
+    BorrowMut(BorrowMutError),
+    /// The [`Mutex`](std::sync::Mutex) or [`RwLock`](std::sync::RwLock) was poisoned.
+    ///
+    /// This requires the `std` feature.
+    #[cfg(feature = "std")]
+    Poisoned,
+}
+
+impl fmt::Display for TryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryError::Borrow(e) => fmt::Display::fmt(e, f),
+            TryError::BorrowMut(e) => fmt::Display::fmt(e, f),
+            #[cfg(feature = "std")]
+            TryError::Poisoned => f.write_str("poisoned lock: another task failed inside"),
+        }
+    }
+}
+
+impl From<Infallible> for TryError {
+    fn from(e: Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl From<BorrowError> for TryError {
+    fn from(e: BorrowError) -> Self {
+        TryError::Borrow(e)
+    }
+}
+
+impl From<BorrowMutError> for TryError {
+    fn from(e: BorrowMutError) -> Self {
+        TryError::BorrowMut(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TryError {}
+
+#[cfg(feature = "std")]
+impl<T> From<std::sync::PoisonError<T>> for TryError {
+    fn from(_: std::sync::PoisonError<T>) -> Self {
+        TryError::Poisoned
+    }
+}
@@ -0,0 +1,233 @@
+use core::cell::{Cell, RefCell};
+use core::mem;
+use core::ptr;
+
+use crate::{Replace, Sentinel};
+
+/// Copies out the value from a reference.
+///
+/// This is the primitive behind [`take_copy`].
+#[diagnostic::on_unimplemented(
+    message = "cannot copy out the value from `{Self}`",
+    label = "the value cannot be copied out of this place",
+    note = "copying out requires the value type to be `Copy`, and the place must be `&mut T`, `&Cell<T>`, `&RefCell<T>`, a lock or an atomic",
+    note = "for a value that is not `Copy`, use `@sentinel`, `@default` or `@clone` instead of `@copy`"
+)]
+pub trait CopyOut<T: Copy> {
+    fn copy_out(self) -> T;
+}
+
+impl<T: Copy> CopyOut<T> for &mut T {
+    fn copy_out(self) -> T {
+        *self
+    }
+}
+
+impl<T: Copy> CopyOut<T> for &Cell<T> {
+    fn copy_out(self) -> T {
+        self.get()
+    }
+}
+
+impl<T: Copy> CopyOut<T> for &RefCell<T> {
+    fn copy_out(self) -> T {
+        *self.borrow()
+    }
+}
+
+/// Clones out the value from a reference.
+///
+/// This is the primitive behind [`take_clone`].
+/// Unlike [`CopyOut`], it is not implemented for [`&Cell<T>`](Cell).
+#[diagnostic::on_unimplemented(
+    message = "cannot clone out the value from `{Self}`",
+    label = "the value cannot be cloned out of this place",
+    note = "cloning out requires the value type to be `Clone`, and the place must be `&mut T`, `&RefCell<T>` or a lock",
+    note = "a `Cell` cannot lend its value to `Clone::clone`; use `@copy`, `@sentinel` or `@default` instead of `@clone`"
+)]
+pub trait CloneOut<T: Clone> {
+    fn clone_out(self) -> T;
+}
+
+impl<T: Clone> CloneOut<T> for &mut T {
+    fn clone_out(self) -> T {
+        self.clone()
+    }
+}
+
+impl<T: Clone> CloneOut<T> for &RefCell<T> {
+    fn clone_out(self) -> T {
+        self.borrow().clone()
+    }
+}
+
+/// Takes out the value from a reference, leaving the default value.
+///
+/// Unlike [`take!`](crate::take!), it only requires ordinary trait bounds,
+/// so it can be used in generic code.
+///
+/// ## Usage
+///
+/// ```rust
+/// fn take_first<T: Default>(v: &mut [T]) -> T {
+///     omniswap::take_default(&mut v[0])
+/// }
+///
+/// let mut v = vec![String::from("a"), String::from("b")];
+/// assert_eq!(take_first(&mut v), "a");
+/// assert_eq!(v, ["", "b"]);
+/// ```
+pub fn take_default<T: Default, P: Replace<T>>(place: P) -> T {
+    place.replace(T::default())
+}
+
+/// Takes out the value from a reference, leaving the sentinel value.
+///
+/// This is a variant of [`take_default`] for [`Sentinel`].
+pub fn take_sentinel<T: Sentinel, P: Replace<T>>(place: P) -> T {
+    place.replace(T::sentinel())
+}
+
+/// Copies out the value from a reference.
+///
+/// This is a variant of [`take_default`] for [`Copy`].
+/// The place keeps its value.
+pub fn take_copy<T: Copy, P: CopyOut<T>>(place: P) -> T {
+    place.copy_out()
+}
+
+/// Clones out the value from a reference.
+///
+/// This is a variant of [`take_default`] for [`Clone`].
+/// The place keeps its value.
+pub fn take_clone<T: Clone, P: CloneOut<T>>(place: P) -> T {
+    place.clone_out()
+}
+
+/// Swaps two possibly overlapping places reached from the same root.
+///
+/// This is a function counterpart of [`swap!`](crate::swap!) for generic code.
+/// Each place is given as a closure projecting the root.
+/// Like [`swap!`](crate::swap!), the first projection is called again to put the value back,
+/// so the projections are called in the order `f`, `g`, `f`.
+/// If `f` and `g` return the same place, it does nothing and `f` is not called again.
+///
+/// It uses the default value as the sentinel; see [`swap_with_sentinel`], [`swap_with_copy`]
+/// and [`swap_with_clone`] for the other strategies.
+///
+/// If `g` panics, the value is put back to the first place through `f` while unwinding.
+///
+/// ## Usage
+///
+/// ```rust
+/// struct Root<T> {
+///     a: Vec<T>,
+///     b: T,
+/// }
+///
+/// fn rotate_in<T: Default>(root: &mut Root<T>, i: usize) {
+///     omniswap::swap_with(root, |r| &mut r.a[i], |r| &mut r.b);
+/// }
+///
+/// let mut root = Root { a: vec![1, 2], b: 3 };
+/// rotate_in(&mut root, 1);
+/// assert_eq!((root.a, root.b), (vec![1, 3], 2));
+/// ```
+pub fn swap_with<R, T, F, G>(root: &mut R, f: F, g: G)
+where
+    R: ?Sized,
+    T: Default,
+    F: FnMut(&mut R) -> &mut T,
+    G: FnOnce(&mut R) -> &mut T,
+{
+    swap_with_by(root, mem::take, f, g)
+}
+
+/// Swaps two possibly overlapping places reached from the same root, using the sentinel value.
+///
+/// This is a variant of [`swap_with`] for [`Sentinel`].
+pub fn swap_with_sentinel<R, T, F, G>(root: &mut R, f: F, g: G)
+where
+    R: ?Sized,
+    T: Sentinel,
+    F: FnMut(&mut R) -> &mut T,
+    G: FnOnce(&mut R) -> &mut T,
+{
+    swap_with_by(root, |x| mem::replace(x, T::sentinel()), f, g)
+}
+
+/// Swaps two possibly overlapping places reached from the same root, copying out the first value.
+///
+/// This is a variant of [`swap_with`] for [`Copy`].
+pub fn swap_with_copy<R, T, F, G>(root: &mut R, f: F, g: G)
+where
+    R: ?Sized,
+    T: Copy,
+    F: FnMut(&mut R) -> &mut T,
+    G: FnOnce(&mut R) -> &mut T,
+{
+    swap_with_by(root, |x| *x, f, g)
+}
+
+/// Swaps two possibly overlapping places reached from the same root, cloning out the first value.
+///
+/// This is a variant of [`swap_with`] for [`Clone`].
+pub fn swap_with_clone<R, T, F, G>(root: &mut R, f: F, g: G)
+where
+    R: ?Sized,
+    T: Clone,
+    F: FnMut(&mut R) -> &mut T,
+    G: FnOnce(&mut R) -> &mut T,
+{
+    swap_with_by(root, |x| x.clone(), f, g)
+}
+
+fn swap_with_by<R, T, F, G, H>(root: &mut R, take: H, mut f: F, g: G)
+where
+    R: ?Sized,
+    F: FnMut(&mut R) -> &mut T,
+    G: FnOnce(&mut R) -> &mut T,
+    H: FnOnce(&mut T) -> T,
+{
+    let x = f(root);
+    let x_ptr: *const T = x;
+    let value = take(x);
+    let mut guard = PutBack {
+        root,
+        f,
+        value: Some(value),
+    };
+    let y = g(guard.root);
+    let value = guard
+        .value
+        .take()
+        .expect("the value is held until the swap");
+    if ptr::eq(y, x_ptr) {
+        // Both closures denote the same place: just put the value back.
+        *y = value;
+        return;
+    }
+    let value = mem::replace(y, value);
+    *(guard.f)(guard.root) = value;
+}
+
+/// The value taken out of the first place, put back if `g` panics.
+struct PutBack<'a, R: ?Sized, T, F>
+where
+    F: FnMut(&mut R) -> &mut T,
+{
+    root: &'a mut R,
+    f: F,
+    value: Option<T>,
+}
+
+impl<R: ?Sized, T, F> Drop for PutBack<'_, R, T, F>
+where
+    F: FnMut(&mut R) -> &mut T,
+{
+    fn drop(&mut self) {
+        if let Some(value) = self.value.take() {
+            *(self.f)(self.root) = value;
+        }
+    }
+}
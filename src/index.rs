@@ -0,0 +1,98 @@
+/// Swaps two elements of a nested array, slice or [`Vec`](std::vec::Vec) without a sentinel.
+///
+/// It takes the common base and two index paths into it.
+/// Therefore, unlike [`swap!`](crate::swap!), it works for any element type
+/// and does not use unsafe code.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut a = [[1, 2], [3, 4]];
+/// omniswap::swap_index!(a; [0][0], [1][1]);
+/// assert_eq!(a, [[4, 2], [3, 1]]);
+/// ```
+///
+/// It works for any depth of nesting, as long as the two paths have the same length.
+///
+/// ```rust
+/// let mut a: Vec<Vec<Box<dyn Fn() -> i32>>> = vec![
+///     vec![Box::new(|| 1), Box::new(|| 2)],
+///     vec![Box::new(|| 3)],
+/// ];
+/// omniswap::swap_index!(a; [0][1], [1][0]);
+/// assert_eq!((a[0][1](), a[1][0]()), (3, 2));
+/// ```
+///
+/// ## How it works
+///
+/// It walks the two paths from the base while their indices are the same.
+///
+/// - If the paths do not diverge until the last index, it uses [`<[T]>::swap`][slice::swap()].
+/// - Otherwise, it uses [`<[T]>::split_at_mut`][slice::split_at_mut()] to obtain
+///   disjoint references at the first diverging index, and [`core::mem::swap`]s
+///   the elements reached from them.
+///
+/// ## Evaluation order
+///
+/// It evaluates each index expression exactly once, in the order of appearance.
+/// The base is evaluated after all the indices, so the indices may refer to it,
+/// as in `swap_index!(a; [0], [a.len() - 1])`.
+///
+/// ## Panics
+///
+/// It panics if an index is out of bounds.
+#[macro_export]
+macro_rules! swap_index {
+    (@bind $base:tt [$($ib:ident)*] [] [[$i:expr] $($irest:tt)*] [$($krest:tt)*]) => {{
+        let i = $i;
+        $crate::swap_index!(@bind $base [$($ib)* i] [] [$($irest)*] [$($krest)*])
+    }};
+    (@bind $base:tt [$($ib:ident)*] [$($kb:ident)*] [] [[$k:expr] $($krest:tt)*]) => {{
+        let k = $k;
+        $crate::swap_index!(@bind $base [$($ib)*] [$($kb)* k] [] [$($krest)*])
+    }};
+    (@bind ($base:expr) [$($ib:ident)*] [$($kb:ident)*] [] []) => {
+        $crate::swap_index!(@go (&mut $base[..]) [$($ib)*] [$($kb)*])
+    };
+    (@go ($s:expr) [$i:ident] [$k:ident]) => {
+        <[_]>::swap($s, $i, $k)
+    };
+    (@go ($s:expr) [$i:ident $($ir:ident)+] [$k:ident $($kr:ident)+]) => {{
+        let s: &mut [_] = $s;
+        if $i == $k {
+            $crate::swap_index!(@go (&mut s[$i][..]) [$($ir)+] [$($kr)+])
+        } else {
+            let (x, y) = $crate::index_pair_mut(s, $i, $k);
+            ::core::mem::swap(&mut x $([$ir])+, &mut y $([$kr])+);
+        }
+    }};
+    (@go $s:tt [$($i:ident)*] [$($k:ident)*]) => {
+        ::core::compile_error!("swap_index!: the index paths must have the same length")
+    };
+    ($base: expr; $([$i: expr])+, $([$k: expr])+) => {
+        $crate::swap_index!(@bind ($base) [] [] [$([$i])+] [$([$k])+])
+    };
+    ($base: expr; $([$i: expr])+, $([$k: expr])+,) => {
+        $crate::swap_index!($base; $([$i])+, $([$k])+)
+    };
+}
+
+/// Internal function used in [`swap_index!`].
+///
+/// Please use [`swap_index!`] instead.
+///
+/// Returns mutable references to two distinct elements of the slice.
+///
+/// ## Panics
+///
+/// It panics if `i == j` or either of them is out of bounds.
+pub fn index_pair_mut<T>(slice: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
+    assert_ne!(i, j, "the indices must be distinct");
+    if i < j {
+        let (left, right) = slice.split_at_mut(j);
+        (&mut left[i], &mut right[0])
+    } else {
+        let (left, right) = slice.split_at_mut(i);
+        (&mut right[0], &mut left[j])
+    }
+}
@@ -86,16 +86,105 @@
 //! The crate provides the following variants:
 //!
 //! - [`rotate!`] -- swaps more than two values at once
+//! - [`permute!`] -- applies several cycles of swaps at once
+//! - [`rotate_left!`], [`rotate_right!`] and `rotate!(k; ...)` -- rotate in either direction, by any number of positions
+//! - [`reverse!`] -- reverses the values of several places
+//! - [`shift!`] -- shifts a new value into a chain of places, returning the value that falls off
+//! - [`swap_if!`] and [`cswap!`] -- swap if a condition holds, the latter without branching
+//! - [`sort2!`], [`sort3!`] and [`sort_places!`] -- sort the values of several places with sorting networks
+//! - [`swap_unchecked!`] and [`rotate_unchecked!`] -- unsafe variants that do not need a sentinel
+//! - [`swap_index!`] -- swaps elements of nested arrays, slices or vectors without a sentinel
+//! - [`try_swap!`], [`try_rotate!`] and [`try_take!`] -- return an error instead of panicking on [`RefCell`](core::cell::RefCell) borrow conflicts
+//! - [`take_default`], [`take_sentinel`], [`take_copy`], [`take_clone`], [`swap_with`] and its variants -- functions usable in generic code
+//! - [`take_strategy!`] and [`swap_strategy!`] -- report whether the value is copied, swapped with a sentinel or default value, cloned, or swapped directly
+//! - [`take_no_clone!`], [`swap_no_clone!`] and [`rotate_no_clone!`] -- fail to compile instead of cloning the value
+//! - [`take_or!`] and the `with` forms of [`take!`], [`swap!`] and [`rotate!`] -- use an explicit sentinel value
+//! - `@copy`, `@sentinel`, `@default` and `@clone` modifiers of [`take!`], [`swap!`] and [`rotate!`] -- pin the strategy
+//! - [`update!`] -- transforms the value of a place through a closure
+//! - [`transfer!`] -- moves a value from one place to another, returning the old value of the destination
+//!
+//!
+//! The macros also accept references to atomics such as `&AtomicUsize`,
+//! optionally with an explicit memory ordering as in `swap!(&a, &b; Ordering::AcqRel)`.
+//!
+//! The crate also exposes [`take!`], [`Replace`], [`TryReplace`] and [`ReplaceOrdered`].
+//! These are primitives used in [`swap!`] and [`rotate!`].
 //!
+//! ## Cargo features
 //!
-//! The crate also exposes [`take!`] and [`Replace`].
-//! These are primitives used in [`swap!`] and [`rotate!`].
+//! - `derive` -- enables `#[derive(Sentinel)]`
+//! - `std` -- adds support for [`&Mutex<T>`](std::sync::Mutex) and [`&RwLock<T>`](std::sync::RwLock) as places
+//!
+//! ## Sentinels
+//!
+//! If your type has a cheap placeholder value but no meaningful [`Default`],
+//! implement [`Sentinel`] so that these macros can use it.
+//! With the `derive` feature, it can also be derived with `#[derive(Sentinel)]`.
 
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+mod atomic;
+mod conditional;
+mod error;
+mod generic;
+mod index;
+mod no_clone;
+mod permute;
 mod replace;
+mod reverse;
+mod rotate_by;
+mod sentinel;
+mod shift;
+mod sort;
+mod step;
+mod strategy;
 mod swap;
+#[cfg(feature = "std")]
+mod sync;
 mod take;
+mod transfer;
+mod unchecked;
+mod update;
+
+pub use crate::atomic::ReplaceOrdered;
+pub use crate::conditional::CSwap;
+pub use crate::error::TryError;
+pub use crate::generic::{
+    swap_with, swap_with_clone, swap_with_copy, swap_with_sentinel, take_clone, take_copy,
+    take_default, take_sentinel, CloneOut, CopyOut,
+};
+pub use crate::replace::{Replace, TryReplace};
+pub use crate::sentinel::Sentinel;
+pub use crate::strategy::Strategy;
+#[cfg(feature = "std")]
+pub use crate::sync::IgnorePoison;
 
-pub use crate::replace::Replace;
-pub use crate::take::{TakeHelper, TakeHelper2, TakeHelper3};
+// Items used in the expansion of the macros.
+#[doc(hidden)]
+pub use crate::index::index_pair_mut;
+#[doc(hidden)]
+pub use crate::no_clone::CloneRejected;
+#[doc(hidden)]
+pub use crate::replace::replace_failed;
+#[doc(hidden)]
+pub use crate::rotate_by::{rotate_by, RotateAccess};
+#[doc(hidden)]
+pub use crate::sort::sort_by_network;
+#[doc(hidden)]
+pub use crate::step::{
+    Held, RotateHelper, StepHelper, StepHelper10, StepHelper11, StepHelper12, StepHelper2,
+    StepHelper3, StepHelper4, StepHelper5, StepHelper6, StepHelper7, StepHelper8, StepHelper9,
+};
+#[doc(hidden)]
+pub use crate::take::{
+    CellTakeable, Movable, TakeHelper, TakeHelper2, TakeHelper3, TakeHelper4, TakeHelper5, Takeable,
+};
+#[doc(hidden)]
+pub use crate::unchecked::UncheckedHelper;
+#[doc(hidden)]
+pub use crate::update::{Reborrow, UpdateGuard};
+#[cfg(feature = "derive")]
+pub use omniswap_derive::Sentinel;
@@ -0,0 +1,104 @@
+/// Internal trait used in [`take_no_clone!`], [`swap_no_clone!`] and [`rotate_no_clone!`].
+///
+/// Please use [`take_no_clone!`], [`swap_no_clone!`] or [`rotate_no_clone!`] instead.
+///
+/// It is not implemented for any type, and cannot be implemented outside this crate,
+/// so that falling back to [`Strategy::Clone`](crate::Strategy::Clone) is reported as an error.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` would be cloned out, which the `*_no_clone!` macros do not allow",
+    label = "`{Self}` is neither `Copy`, `Sentinel` nor `Default`",
+    note = "implement `Sentinel` or `Default` for `{Self}`, or use `take!`, `swap!` or `rotate!` to allow cloning"
+)]
+pub trait CloneRejected: sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A variant of [`take!`](crate::take!) that never clones.
+///
+/// It accepts the same places as [`take!`](crate::take!),
+/// but fails to compile if the value would be cloned out,
+/// that is, if the value type is [`Clone`] but neither [`Copy`], [`Sentinel`](crate::Sentinel) nor [`Default`].
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut v = vec![1, 2, 3];
+/// assert_eq!(omniswap::take_no_clone!(&mut v), [1, 2, 3]);
+/// assert_eq!(v, []);
+/// ```
+///
+/// ```rust,compile_fail
+/// #[derive(Clone)]
+/// struct Mesh(Vec<f32>);
+///
+/// let mut x = Mesh(vec![1.0]);
+/// let _ = omniswap::take_no_clone!(&mut x);
+/// ```
+#[macro_export]
+macro_rules! take_no_clone {
+    ($p: expr) => {
+        $crate::TakeHelper::new($p).take_no_clone()
+    };
+    ($p: expr,) => {
+        $crate::take_no_clone!($p)
+    };
+}
+
+/// A variant of [`swap!`](crate::swap!) that never clones.
+///
+/// It fails to compile if the value of the first place would be cloned out.
+/// See [`take_no_clone!`](crate::take_no_clone!) for details.
+/// Otherwise it works exactly like [`swap!`](crate::swap!), including the place syntax.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut x = String::from("x");
+/// let mut y = String::from("y");
+/// omniswap::swap_no_clone!(&mut x, &mut y);
+/// assert_eq!((x.as_str(), y.as_str()), ("y", "x"));
+/// ```
+///
+/// Cells and locks are swapped directly, so any value type is accepted for them.
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// #[derive(Clone)]
+/// struct Mesh(Vec<f32>);
+///
+/// let x = RefCell::new(Mesh(vec![1.0]));
+/// let y = RefCell::new(Mesh(vec![2.0]));
+/// omniswap::swap_no_clone!(&x, &y);
+/// assert_eq!(x.into_inner().0, [2.0]);
+/// ```
+#[macro_export]
+macro_rules! swap_no_clone {
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl no_clone swap [] $($args)*)
+    };
+}
+
+/// A variant of [`rotate!`](crate::rotate!) that never clones.
+///
+/// It fails to compile if the value of the first place would be cloned out.
+/// See [`take_no_clone!`](crate::take_no_clone!) for details.
+/// Otherwise it works exactly like [`rotate!`](crate::rotate!).
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut x = vec!['a'];
+/// let mut y = vec!['b'];
+/// let mut z = vec!['c'];
+/// omniswap::rotate_no_clone!(&mut x, &mut y, &mut z);
+/// assert_eq!((x, y, z), (vec!['c'], vec!['a'], vec!['b']));
+/// ```
+#[macro_export]
+macro_rules! rotate_no_clone {
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl no_clone rotate [] $($args)*)
+    };
+}
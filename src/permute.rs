@@ -0,0 +1,38 @@
+/// Applies a permutation written in cycle notation.
+///
+/// Each parenthesized group is a cycle, applied as in [`rotate!`](crate::rotate!):
+/// `(a, b, c)` moves the value of `a` to `b`, `b` to `c`, and `c` to `a`.
+/// The cycles are applied from left to right, in one invocation.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut a = [1, 2, 3, 4, 5];
+/// omniswap::permute!((mut a[0], mut a[1], mut a[2]), (mut a[3], mut a[4]));
+/// assert_eq!(a, [3, 1, 2, 5, 4]);
+/// ```
+///
+/// Each cycle accepts the same arguments as [`rotate!`](crate::rotate!),
+/// including references to cells and locks and the place syntax.
+/// The cycles are expected to be disjoint; if they share a place,
+/// the result is the composition of the cycles in the order of appearance.
+///
+/// ## Evaluation order
+///
+/// It evaluates the cycles from left to right,
+/// each in the same order as [`rotate!`](crate::rotate!).
+///
+/// ## Panics
+///
+/// If a place turns out to be unavailable, the cycle being applied is rolled back
+/// as in [`rotate!`](crate::rotate!), but the cycles already applied are kept.
+#[macro_export]
+macro_rules! permute {
+    ($(($($cycle: tt)*)),* $(,)?) => {
+        {
+            $(
+                $crate::rotate!($($cycle)*);
+            )*
+        }
+    };
+}
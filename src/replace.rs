@@ -1,4 +1,6 @@
-use core::cell::{Cell, RefCell};
+use core::cell::{BorrowMutError, Cell, RefCell};
+use core::convert::Infallible;
+use core::fmt;
 use core::mem;
 
 /// Takes out the value from a reference, leaving another value you supplied.
@@ -18,21 +20,76 @@ pub trait Replace<T> {
     fn replace(self, value: T) -> T;
 }
 
-impl<'a, T> Replace<T> for &'a mut T {
+impl<T> Replace<T> for &mut T {
     fn replace(self, value: T) -> T {
         mem::replace(self, value)
     }
 }
 
-impl<'a, T> Replace<T> for &'a Cell<T> {
+impl<T> Replace<T> for &Cell<T> {
     fn replace(self, value: T) -> T {
         self.replace(value)
     }
 }
 
-impl<'a, T> Replace<T> for &'a RefCell<T> {
+impl<T> Replace<T> for &RefCell<T> {
     fn replace(self, value: T) -> T {
         let mut r = self.borrow_mut();
         mem::replace(&mut *r, value)
     }
 }
+
+/// A fallible variant of [`Replace`].
+///
+/// Instead of panicking when the place is temporarily unavailable
+/// (for example, when the [`RefCell`] is already borrowed),
+/// it gives back the value you supplied along with the error.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use omniswap::TryReplace;
+/// let x = RefCell::new(42);
+/// let guard = x.borrow();
+/// let (_, value) = TryReplace::try_replace(&x, 84).unwrap_err();
+/// assert_eq!(value, 84);
+/// drop(guard);
+/// assert_eq!(TryReplace::try_replace(&x, 84).ok(), Some(42));
+/// ```
+pub trait TryReplace<T>: Replace<T> {
+    type Error;
+    fn try_replace(self, value: T) -> Result<T, (Self::Error, T)>;
+}
+
+impl<T> TryReplace<T> for &mut T {
+    type Error = Infallible;
+    fn try_replace(self, value: T) -> Result<T, (Self::Error, T)> {
+        Ok(mem::replace(self, value))
+    }
+}
+
+impl<T> TryReplace<T> for &Cell<T> {
+    type Error = Infallible;
+    fn try_replace(self, value: T) -> Result<T, (Self::Error, T)> {
+        Ok(self.replace(value))
+    }
+}
+
+impl<T> TryReplace<T> for &RefCell<T> {
+    type Error = BorrowMutError;
+    fn try_replace(self, value: T) -> Result<T, (Self::Error, T)> {
+        match self.try_borrow_mut() {
+            Ok(mut r) => Ok(mem::replace(&mut *r, value)),
+            Err(e) => Err((e, value)),
+        }
+    }
+}
+
+/// Internal function used in [`rotate!`](crate::rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) instead.
+#[track_caller]
+pub fn replace_failed<E: fmt::Display>(error: E) -> ! {
+    panic!("{}", error)
+}
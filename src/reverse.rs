@@ -0,0 +1,68 @@
+/// Reverses the values of the places.
+///
+/// It swaps the first place with the last one, the second one with the second to last one,
+/// and so on, as in [`swap!`](crate::swap!).
+/// Each swap uses at most one sentinel, and none if the first place of the pair is a cell or a lock.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut m = [[1, 2], [3, 4]];
+/// omniswap::reverse!(mut m[0][0], mut m[0][1], mut m[1][0], mut m[1][1]);
+/// assert_eq!(m, [[4, 3], [2, 1]]);
+/// ```
+///
+/// The places may be any mix of `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell),
+/// and the place syntax lets them index into the same array as above.
+/// Since each pair is swapped on its own, the places of a pair must be distinct,
+/// while the middle place of an odd number of places is never accessed and may be borrowed.
+///
+/// ## Evaluation order
+///
+/// It evaluates the pairs from the outermost one, each in the same order as [`swap!`](crate::swap!).
+/// If the number of places is odd, the middle argument is evaluated last, but not accessed.
+/// If the place syntax is used, all the index expressions are evaluated first,
+/// in the order of appearance.
+///
+/// ## Panics
+///
+/// If a place turns out to be unavailable, the pair being swapped is left as is
+/// as in [`swap!`](crate::swap!), but the pairs already swapped are kept.
+#[macro_export]
+macro_rules! reverse {
+    ($($args: tt)*) => {
+        $crate::__place_args!(__reverse_impl panic reverse [] $($args)*)
+    };
+}
+
+/// Internal macro used in [`reverse!`].
+///
+/// Please use [`reverse!`] instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reverse_impl {
+    (@start $mode: tt reverse [$($p: expr),*]) => {
+        $crate::__reverse_impl!(@list $mode [$($p),*] [] $($p),*)
+    };
+    (@list $mode: tt [$($p: expr),*] [$($r: expr),*] $y: expr $(, $rest: expr)*) => {
+        $crate::__reverse_impl!(@list $mode [$($p),*] [$y $(, $r)*] $($rest),*)
+    };
+    (@list $mode: tt [$($p: expr),*] [$($r: expr),*]) => {
+        $crate::__reverse_impl!(@pairs $mode [$($p),*] [$($r),*] [$($p),*])
+    };
+    // The third list is consumed twice as fast, so that only the first half is swapped.
+    (@pairs $mode: tt [$($p: expr),*] [$($r: expr),*] []) => {
+        ()
+    };
+    (@pairs $mode: tt [$m: expr $(, $p: expr)*] [$($r: expr),*] [$h: expr]) => {
+        {
+            let _ = $m;
+        }
+    };
+    (@pairs $mode: tt [$x: expr $(, $p: expr)*] [$y: expr $(, $r: expr)*] [$h1: expr, $h2: expr $(, $h: expr)*]) => {
+        {
+            $crate::__rotate_impl!(@start $mode rotate [$x, $y]);
+            $crate::__reverse_impl!(@pairs $mode [$($p),*] [$($r),*] [$($h),*])
+        }
+    };
+}
@@ -0,0 +1,231 @@
+use crate::{replace_failed, TryError};
+
+/// Internal type used in [`rotate!`](crate::rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) instead.
+///
+/// An access to a place requested by [`rotate_by`], with the value carried in a slot.
+/// The place is evaluated before the slot is touched, and the slot keeps its value on failure.
+pub enum RotateAccess {
+    /// Takes out the value into the empty slot, as in [`try_take!`](crate::try_take!).
+    Take,
+    /// Exchanges the value in the slot with the value of the place,
+    /// as in [`TryReplace`](crate::TryReplace).
+    Replace,
+}
+
+/// Internal function used in [`rotate!`](crate::rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) instead.
+///
+/// Rotates `n` places to the right by `k` positions.
+/// The places are accessed through `access` by their indices.
+///
+/// The rotation is decomposed into `gcd(n, k)` cycles, each of which takes out only one value.
+/// If an access fails, the values are put back and the error is returned.
+/// If an access panics, the values are put back as far as possible while unwinding.
+pub fn rotate_by<T, F>(n: usize, k: usize, access: F) -> Result<(), TryError>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    if n == 0 {
+        return Ok(());
+    }
+    let k = k % n;
+    if k == 0 {
+        return Ok(());
+    }
+    let mut cycles = Cycles {
+        access,
+        n,
+        k,
+        done: 0,
+        pos: 0,
+        slot: None,
+        armed: true,
+    };
+    for start in 0..gcd(n, k) {
+        cycles.pos = start;
+        cycles.access(start, RotateAccess::Take)?;
+        loop {
+            let j = (cycles.pos + k) % n;
+            cycles.access(j, RotateAccess::Replace)?;
+            if j == start {
+                break;
+            }
+            cycles.pos = j;
+        }
+        cycles.slot = None;
+        cycles.done = start + 1;
+    }
+    cycles.armed = false;
+    Ok(())
+}
+
+/// The progress of [`rotate_by`], rotated back on failure or on unwind.
+struct Cycles<T, F>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    access: F,
+    n: usize,
+    k: usize,
+    /// The number of completed cycles, the next of which starts at this index.
+    done: usize,
+    /// The last place updated in the current cycle, or its start.
+    pos: usize,
+    /// The value in flight, taken out of `pos`.
+    slot: Option<T>,
+    armed: bool,
+}
+
+impl<T, F> Cycles<T, F>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    fn access(&mut self, i: usize, kind: RotateAccess) -> Result<(), TryError> {
+        (self.access)(i, kind, &mut self.slot).inspect_err(|_| self.undo(true))
+    }
+
+    /// Puts the value in the slot back to the `i`-th place, and reports whether it succeeded.
+    fn put(&mut self, i: usize, strict: bool) -> bool {
+        match (self.access)(i, RotateAccess::Replace, &mut self.slot) {
+            Ok(()) => true,
+            Err(e) if strict => replace_failed(e),
+            Err(_) => false,
+        }
+    }
+
+    /// Walks the current cycle and the completed cycles back.
+    ///
+    /// If not `strict`, it stops at the first failure instead of panicking,
+    /// since a second panic while unwinding would abort.
+    fn undo(&mut self, strict: bool) {
+        self.armed = false;
+        let (n, k, start) = (self.n, self.k, self.done);
+        if self.slot.is_some() {
+            while self.pos != start {
+                if !self.put(self.pos, strict) {
+                    return;
+                }
+                self.pos = (self.pos + n - k) % n;
+            }
+            if !self.put(start, strict) {
+                return;
+            }
+            self.slot = None;
+        } else if self.pos != start {
+            // The value in flight was lost in a panicking access.
+            return;
+        }
+        for start in (0..start).rev() {
+            match (self.access)(start, RotateAccess::Take, &mut self.slot) {
+                Ok(()) => {}
+                Err(e) if strict => replace_failed(e),
+                Err(_) => return,
+            }
+            let mut i = start;
+            loop {
+                i = (i + n - k) % n;
+                if !self.put(i, strict) {
+                    return;
+                }
+                if i == start {
+                    break;
+                }
+            }
+            self.slot = None;
+        }
+    }
+}
+
+impl<T, F> Drop for Cycles<T, F>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    fn drop(&mut self) {
+        if self.armed {
+            self.undo(false);
+        }
+    }
+}
+
+/// Puts the value in `slot` to the `i`-th place, leaving the value replaced in it.
+pub(crate) fn put<T, F>(access: &mut F, i: usize, slot: &mut Option<T>)
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    access(i, RotateAccess::Replace, slot).unwrap_or_else(|e| replace_failed(e));
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Internal macro used in [`rotate!`](crate::rotate!), [`rotate_left!`](crate::rotate_left!)
+/// and [`try_rotate!`](crate::try_rotate!) with the number of positions.
+///
+/// Please use them instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rotate_by_impl {
+    (@start (by $m: ident $k: ident) rotate_left [$($p: expr),*]) => {
+        {
+            let n = <[()]>::len(&[$($crate::__rotate_by_impl!(@unit $p)),*]);
+            let $k = if n == 0 { 0 } else { n - $k % n };
+            $crate::__rotate_by_impl!(@start (by $m $k) rotate [$($p),*])
+        }
+    };
+    (@start (by $m: ident $k: ident) rotate [$($p: expr),*]) => {
+        {
+            let n = <[()]>::len(&[$($crate::__rotate_by_impl!(@unit $p)),*]);
+            let result = $crate::rotate_by(n, $k, |i, access, slot| {
+                $crate::__rotate_by_impl!(@arms i access slot [0] $($p),*)
+            });
+            $crate::__rotate_by_impl!(@done $m result)
+        }
+    };
+    (@unit $p: expr) => {
+        ()
+    };
+    (@arms $i: ident $access: ident $slot: ident [$($j: tt)*] $p: expr $(, $rest: expr)*) => {
+        if $i == $($j)* {
+            match ($p, $access) {
+                (place, $crate::RotateAccess::Take) => {
+                    $crate::TakeHelper::new(place).try_take().map(|value| {
+                        *$slot = ::core::option::Option::Some(value);
+                    })
+                }
+                (place, $crate::RotateAccess::Replace) => {
+                    let value = $slot.take().expect("the slot holds the value to put");
+                    match $crate::TryReplace::try_replace(place, value) {
+                        ::core::result::Result::Ok(value) => {
+                            *$slot = ::core::option::Option::Some(value);
+                            ::core::result::Result::Ok(())
+                        }
+                        ::core::result::Result::Err((e, value)) => {
+                            *$slot = ::core::option::Option::Some(value);
+                            ::core::result::Result::Err(::core::convert::From::from(e))
+                        }
+                    }
+                }
+            }
+        } else {
+            $crate::__rotate_by_impl!(@arms $i $access $slot [$($j)* + 1] $($rest),*)
+        }
+    };
+    (@arms $i: ident $access: ident $slot: ident [$($j: tt)*]) => {
+        ::core::unreachable!()
+    };
+    (@done panic $result: ident) => {
+        if let ::core::result::Result::Err(error) = $result {
+            $crate::replace_failed(error)
+        }
+    };
+    (@done try $result: ident) => {
+        $result
+    };
+}
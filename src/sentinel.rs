@@ -0,0 +1,117 @@
+use core::array;
+use core::marker::PhantomData;
+
+/// Types that have a cheap placeholder value to leave behind.
+///
+/// [`take!`](crate::take!), [`swap!`](crate::swap!) and [`rotate!`](crate::rotate!) temporarily put a dummy value
+/// into a place while the original value is moved around.
+/// Implementing this trait lets them use that dummy value
+/// even if the type has no meaningful [`Default`](std::default::Default),
+/// or has one that is expensive to construct.
+///
+/// The sentinel is preferred over [`Default`](std::default::Default)
+/// and [`Clone`](std::clone::Clone), but not over [`Copy`](std::marker::Copy).
+///
+/// ## Usage
+///
+/// ```rust
+/// # use omniswap::Sentinel;
+/// struct Handle {
+///     id: u32,
+/// }
+///
+/// impl Sentinel for Handle {
+///     fn sentinel() -> Self {
+///         Handle { id: u32::MAX }
+///     }
+/// }
+///
+/// let mut a = [[Handle { id: 1 }, Handle { id: 2 }], [Handle { id: 3 }, Handle { id: 4 }]];
+/// omniswap::swap!(&mut a[0][0], &mut a[1][1]);
+/// assert_eq!((a[0][0].id, a[1][1].id), (4, 1));
+/// ```
+///
+/// ## Deriving
+///
+/// With the `derive` feature enabled, `#[derive(Sentinel)]` is available.
+///
+/// For structs, the sentinel is built field by field.
+/// Fields marked `#[sentinel(default)]` use [`Default`](std::default::Default) instead.
+///
+/// For enums, the sentinel is the unit variant marked `#[sentinel]`.
+///
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # {
+/// # use omniswap::Sentinel;
+/// #[derive(Sentinel)]
+/// enum State {
+///     #[sentinel]
+///     Empty,
+///     Running(Box<dyn FnMut()>),
+/// }
+///
+/// #[derive(Sentinel)]
+/// struct Slot {
+///     id: u32,
+///     state: State,
+/// }
+/// # }
+/// ```
+pub trait Sentinel {
+    /// Returns the placeholder value.
+    ///
+    /// The value is only observable while the swapping is in progress
+    /// (or after a [`take!`](crate::take!)), so it need not be meaningful.
+    fn sentinel() -> Self;
+}
+
+macro_rules! impl_sentinel {
+    ($($ty:ty => $value:expr,)*) => {
+        $(
+            impl Sentinel for $ty {
+                fn sentinel() -> Self {
+                    $value
+                }
+            }
+        )*
+    };
+}
+
+impl_sentinel! {
+    () => (),
+    bool => false,
+    char => '\0',
+    u8 => 0,
+    u16 => 0,
+    u32 => 0,
+    u64 => 0,
+    u128 => 0,
+    usize => 0,
+    i8 => 0,
+    i16 => 0,
+    i32 => 0,
+    i64 => 0,
+    i128 => 0,
+    isize => 0,
+    f32 => 0.0,
+    f64 => 0.0,
+}
+
+impl<T> Sentinel for Option<T> {
+    fn sentinel() -> Self {
+        None
+    }
+}
+
+impl<T: ?Sized> Sentinel for PhantomData<T> {
+    fn sentinel() -> Self {
+        PhantomData
+    }
+}
+
+impl<T: Sentinel, const N: usize> Sentinel for [T; N] {
+    fn sentinel() -> Self {
+        array::from_fn(|_| T::sentinel())
+    }
+}
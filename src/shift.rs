@@ -0,0 +1,91 @@
+/// Shifts a new value into a chain of places, and returns the value that falls off the end.
+///
+/// `shift!(value => x, y, z)` puts `value` to `x`, the old value of `x` to `y`,
+/// and the old value of `y` to `z`, and then returns the old value of `z`.
+/// It is [`rotate!`](crate::rotate!) without the wrap-around.
+///
+/// It needs no sentinel, since the new value takes its role.
+/// Therefore the value type need not satisfy any trait.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut history = [None, None, None];
+/// for x in 1..=4 {
+///     let dropped = omniswap::shift!(Some(x) => mut history[0], mut history[1], mut history[2]);
+///     assert_eq!(dropped, (x > 3).then(|| x - 3));
+/// }
+/// assert_eq!(history, [Some(4), Some(3), Some(2)]);
+/// ```
+///
+/// The places may be any mix of `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell),
+/// and the place syntax lets them index into the same array as above.
+/// With a single place, it is the same as [`Replace::replace`](crate::Replace::replace).
+///
+/// ## Evaluation order
+///
+/// It evaluates the new value and then the places in the order of appearance, each exactly once.
+/// If the place syntax is used, the index expressions are evaluated after the new value,
+/// in the order of appearance.
+///
+/// ## Panics
+///
+/// If a place turns out to be unavailable, for example because the
+/// [`RefCell`](std::cell::RefCell) is already borrowed,
+/// it puts the original values back to the places already updated and then panics.
+/// To do so, it evaluates these arguments again, in the reverse order.
+///
+/// This does not apply to panics raised while evaluating the arguments themselves,
+/// for example an index out of bounds. As in [`rotate!`](crate::rotate!),
+/// the arguments are evaluated in place, so that `?` and `return` in them work as usual,
+/// and nothing can be put back while unwinding:
+/// the places already updated keep their new values, and the value being moved is lost.
+///
+/// ```rust
+/// # use std::panic::{catch_unwind, AssertUnwindSafe};
+/// let mut a = String::from("a");
+/// let mut w = vec![String::from("w")];
+/// let result = catch_unwind(AssertUnwindSafe(|| {
+///     omniswap::shift!(String::from("new") => &mut a, &mut w[3])
+/// }));
+/// assert!(result.is_err());
+/// // The old value of `a` is lost.
+/// assert_eq!((a.as_str(), w[0].as_str()), ("new", "w"));
+/// ```
+#[macro_export]
+macro_rules! shift {
+    ($new: expr => $($args: tt)*) => {
+        {
+            let value = $new;
+            $crate::__place_args!(__shift_impl (with value) shift [] $($args)*)
+        }
+    };
+}
+
+/// Internal macro used in [`shift!`].
+///
+/// Please use [`shift!`] instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __shift_impl {
+    (@start (with $value: ident) shift [$($p: expr),*]) => {
+        $crate::__shift_impl!(@step [] $value; $($p),*)
+    };
+    (@step [$($done: expr),*] $value: ident;) => {
+        $value
+    };
+    (@step [$($done: expr),*] $value: ident; $y: expr $(, $rest: expr)*) => {
+        match $crate::StepHelper::new($value, $y).try_step() {
+            ::core::result::Result::Ok($value) => {
+                $crate::__shift_impl!(@step [$y $(, $done)*] $value; $($rest),*)
+            }
+            ::core::result::Result::Err((error, $value)) => {
+                $(
+                    let $value = $crate::StepHelper::new($value, $done).step();
+                )*
+                let _ = $value;
+                $crate::replace_failed(error)
+            }
+        }
+    };
+}
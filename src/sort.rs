@@ -0,0 +1,304 @@
+use core::cmp::Ordering;
+
+use crate::rotate_by::put;
+use crate::{replace_failed, RotateAccess, TryError};
+
+/// Optimal sorting networks for up to eight places, as pairs of indices to compare and exchange.
+const NETWORKS: [&[(usize, usize)]; 9] = [
+    &[],
+    &[],
+    &[(0, 1)],
+    &[(0, 2), (0, 1), (1, 2)],
+    &[(0, 2), (1, 3), (0, 1), (2, 3), (1, 2)],
+    &[
+        (0, 3),
+        (1, 4),
+        (0, 2),
+        (1, 3),
+        (0, 1),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (2, 3),
+    ],
+    &[
+        (0, 5),
+        (1, 3),
+        (2, 4),
+        (1, 2),
+        (3, 4),
+        (0, 3),
+        (2, 5),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+    ],
+    &[
+        (0, 6),
+        (2, 3),
+        (4, 5),
+        (0, 2),
+        (1, 4),
+        (3, 6),
+        (0, 1),
+        (2, 5),
+        (3, 4),
+        (1, 2),
+        (4, 6),
+        (2, 3),
+        (4, 5),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+    &[
+        (0, 2),
+        (1, 3),
+        (4, 6),
+        (5, 7),
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+        (0, 1),
+        (2, 3),
+        (4, 5),
+        (6, 7),
+        (2, 4),
+        (3, 5),
+        (1, 4),
+        (3, 6),
+        (1, 2),
+        (3, 4),
+        (5, 6),
+    ],
+];
+
+/// Internal function used in [`sort_places!`](crate::sort_places!).
+///
+/// Please use [`sort_places!`](crate::sort_places!) instead.
+///
+/// Sorts `n` places with a sorting network.
+/// The places are accessed through `access` by their indices.
+///
+/// It uses an optimal network for up to eight places, and an insertion network otherwise.
+pub fn sort_by_network<T, C, F>(n: usize, mut cmp: C, mut access: F)
+where
+    C: FnMut(&T, &T) -> Ordering,
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    if let Some(network) = NETWORKS.get(n) {
+        for &(i, j) in network.iter() {
+            compare_exchange(i, j, &mut cmp, &mut access);
+        }
+    } else {
+        for i in 1..n {
+            for j in (1..=i).rev() {
+                compare_exchange(j - 1, j, &mut cmp, &mut access);
+            }
+        }
+    }
+}
+
+/// Puts the smaller value to the `i`-th place and the larger one to the `j`-th place.
+fn compare_exchange<T, C, F>(i: usize, j: usize, cmp: &mut C, access: &mut F)
+where
+    C: FnMut(&T, &T) -> Ordering,
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    let mut guard = PutBack {
+        access,
+        i,
+        j,
+        a: None,
+        b: None,
+    };
+    // The value of the `i`-th place is held by the guard while the `j`-th place is evaluated.
+    (guard.access)(i, RotateAccess::Take, &mut guard.a).unwrap_or_else(|e| replace_failed(e));
+    (guard.access)(j, RotateAccess::Take, &mut guard.b).unwrap_or_else(|e| replace_failed(e));
+    let less = match (&guard.a, &guard.b) {
+        (Some(a), Some(b)) => cmp(b, a) == Ordering::Less,
+        _ => unreachable!("the values are held until compared"),
+    };
+    let (mut a, mut b) = (guard.a.take(), guard.b.take());
+    if less {
+        core::mem::swap(&mut a, &mut b);
+    }
+    put(guard.access, i, &mut a);
+    put(guard.access, j, &mut b);
+}
+
+/// The values taken out for comparison, put back if evaluating a place or the comparator panics.
+struct PutBack<'a, T, F>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    access: &'a mut F,
+    i: usize,
+    j: usize,
+    a: Option<T>,
+    b: Option<T>,
+}
+
+impl<T, F> Drop for PutBack<'_, T, F>
+where
+    F: FnMut(usize, RotateAccess, &mut Option<T>) -> Result<(), TryError>,
+{
+    fn drop(&mut self) {
+        // The places were just accessed, and a second panic here would abort.
+        if let Some(a) = self.a.take() {
+            let _ = (self.access)(self.i, RotateAccess::Replace, &mut Some(a));
+        }
+        if let Some(b) = self.b.take() {
+            let _ = (self.access)(self.j, RotateAccess::Replace, &mut Some(b));
+        }
+    }
+}
+
+/// Sorts the values of two places.
+///
+/// It is a shorthand for [`sort_places!`](crate::sort_places!) with exactly two places.
+/// This is useful, for example, to normalize an edge `(u, v)` so that `u <= v`.
+///
+/// ## Usage
+///
+/// ```rust
+/// struct Edge {
+///     u: u32,
+///     v: u32,
+/// }
+///
+/// let mut e = Edge { u: 5, v: 2 };
+/// omniswap::sort2!(&mut e.u, &mut e.v);
+/// assert_eq!((e.u, e.v), (2, 5));
+/// ```
+#[macro_export]
+macro_rules! sort2 {
+    (by $cmp: expr; $($args: tt)*) => {
+        {
+            let cmp = $cmp;
+            $crate::__place_args!(__sort_impl (sort cmp) sort2 [] $($args)*)
+        }
+    };
+    ($($args: tt)*) => {
+        $crate::sort2!(by ::core::cmp::Ord::cmp; $($args)*)
+    };
+}
+
+/// Sorts the values of three places.
+///
+/// It is a shorthand for [`sort_places!`](crate::sort_places!) with exactly three places.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut p = [[3.0f64, 1.0, 2.0]];
+/// omniswap::sort3!(by |a: &f64, b: &f64| a.total_cmp(b); mut p[0][0], mut p[0][1], mut p[0][2]);
+/// assert_eq!(p, [[1.0, 2.0, 3.0]]);
+/// ```
+#[macro_export]
+macro_rules! sort3 {
+    (by $cmp: expr; $($args: tt)*) => {
+        {
+            let cmp = $cmp;
+            $crate::__place_args!(__sort_impl (sort cmp) sort3 [] $($args)*)
+        }
+    };
+    ($($args: tt)*) => {
+        $crate::sort3!(by ::core::cmp::Ord::cmp; $($args)*)
+    };
+}
+
+/// Sorts the values held in the places.
+///
+/// It sorts by [`Ord`] by default,
+/// or by a comparator given with `by`, as in [`slice::sort_by`].
+/// The sort is not stable.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// let mut m = [[4, 1], [3, 2]];
+/// omniswap::sort_places!(mut m[0][0], mut m[0][1], mut m[1][0], mut m[1][1]);
+/// assert_eq!(m, [[1, 2], [3, 4]]);
+///
+/// let x = RefCell::new(String::from("bb"));
+/// let mut y = String::from("a");
+/// let z = RefCell::new(String::from("ccc"));
+/// omniswap::sort_places!(by |a: &String, b: &String| b.len().cmp(&a.len()); &x, &mut y, &z);
+/// assert_eq!((x.into_inner(), y, z.into_inner()), ("ccc".into(), "bb".into(), "a".into()));
+/// ```
+///
+/// As the second example shows, the places may be any mix of
+/// `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell),
+/// and the place syntax lets them index into the same array as the first one does.
+/// The places must be distinct.
+///
+/// ## Sorting networks
+///
+/// It runs a fixed sequence of compare-exchange operations on pairs of places.
+/// For up to eight places, the sequence has the minimum known number of comparisons:
+/// 1, 3, 5, 9, 12, 16 and 19 for two to eight places.
+/// For more places, it falls back to an insertion network of `n * (n - 1) / 2` comparisons.
+///
+/// Each compare-exchange takes out the two values as in [`take!`](crate::take!)
+/// and puts them back in order, so the requirements are the same as [`take!`](crate::take!).
+///
+/// ## Evaluation order
+///
+/// It evaluates the comparator first.
+/// The places are evaluated as they are accessed:
+/// each compare-exchange of the `i`-th and `j`-th places evaluates them in the order `i`, `j`, `i`, `j`.
+/// If the place syntax is used, all the index expressions are evaluated after the comparator,
+/// in the order of appearance.
+///
+/// ## Panics
+///
+/// If a place turns out to be unavailable, for example because the
+/// [`RefCell`](std::cell::RefCell) is already borrowed,
+/// the values of the pair being compared are put back and then it panics.
+/// The same applies if the comparator panics, or if evaluating an argument panics.
+/// The exchanges already done are kept.
+#[macro_export]
+macro_rules! sort_places {
+    (by $cmp: expr; $($args: tt)*) => {
+        {
+            let cmp = $cmp;
+            $crate::__place_args!(__sort_impl (sort cmp) sort_places [] $($args)*)
+        }
+    };
+    ($($args: tt)*) => {
+        $crate::sort_places!(by ::core::cmp::Ord::cmp; $($args)*)
+    };
+}
+
+/// Internal macro used in [`sort2!`], [`sort3!`] and [`sort_places!`].
+///
+/// Please use them instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sort_impl {
+    (@start (sort $cmp: ident) sort2 [$x: expr, $y: expr]) => {
+        $crate::__sort_impl!(@start (sort $cmp) sort_places [$x, $y])
+    };
+    (@start $mode: tt sort2 [$($out: expr),*]) => {
+        ::core::compile_error!("expected exactly two places")
+    };
+    (@start (sort $cmp: ident) sort3 [$x: expr, $y: expr, $z: expr]) => {
+        $crate::__sort_impl!(@start (sort $cmp) sort_places [$x, $y, $z])
+    };
+    (@start $mode: tt sort3 [$($out: expr),*]) => {
+        ::core::compile_error!("expected exactly three places")
+    };
+    (@start (sort $cmp: ident) sort_places [$($p: expr),*]) => {
+        {
+            let n = <[()]>::len(&[$($crate::__rotate_by_impl!(@unit $p)),*]);
+            $crate::sort_by_network(n, $cmp, |i, access, slot| {
+                $crate::__rotate_by_impl!(@arms i access slot [0] $($p),*)
+            })
+        }
+    };
+}
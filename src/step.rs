@@ -0,0 +1,558 @@
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use core::mem;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+
+use crate::{
+    replace_failed, CellTakeable, Replace, Sentinel, Strategy, TakeHelper, Takeable, TryError,
+    TryReplace,
+};
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// It extends [`TakeHelper`] with places that can be swapped directly.
+/// For such places, `take` does not take out the value but returns [`Held`].
+pub struct RotateHelper<P>(pub(crate) TakeHelper<P>);
+
+impl<P> RotateHelper<P> {
+    pub fn new(place: P) -> Self {
+        RotateHelper(TakeHelper::new(place))
+    }
+}
+
+impl<P> Deref for RotateHelper<P> {
+    type Target = TakeHelper<P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<P> DerefMut for RotateHelper<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// The first place of a rotation, kept as is instead of taking out its value.
+/// Each step swaps the value of this place with the next one.
+#[derive(Debug, Clone, Copy)]
+pub struct Held<P>(pub(crate) P);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// It carries the value taken out so far (or a [`Held`] place) into the next place.
+pub struct StepHelper<S, P>(pub(crate) StepHelper2<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper2<S, P>(pub(crate) StepHelper3<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// The last resort for the values stepped through places that only implement [`Replace`].
+pub struct StepHelper3<S, P>(pub(crate) StepHelper4<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// It and the following levels take out the value of a [`Held`] place as in [`take!`](crate::take!),
+/// for the places that cannot be swapped with it directly:
+/// first the places implementing [`TryReplace`], and then those only implementing [`Replace`].
+pub struct StepHelper4<S, P>(pub(crate) StepHelper5<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper5<S, P>(pub(crate) StepHelper6<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper6<S, P>(pub(crate) StepHelper7<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper7<S, P>(pub(crate) StepHelper8<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper8<S, P>(pub(crate) StepHelper9<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper9<S, P>(pub(crate) StepHelper10<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper10<S, P>(pub(crate) StepHelper11<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+pub struct StepHelper11<S, P>(pub(crate) StepHelper12<S, P>);
+
+/// Internal type used in [`rotate!`](crate::rotate!) and [`try_rotate!`](crate::try_rotate!).
+///
+/// Please use [`rotate!`](crate::rotate!) or [`try_rotate!`](crate::try_rotate!) instead.
+///
+/// It catches the [`Held`] places whose value cannot be taken out,
+/// so that the error is reported as in [`take!`](crate::take!).
+pub struct StepHelper12<S, P>(pub(crate) Option<(S, P)>);
+
+/// The result of a step: the next state, or the current state along with the error.
+pub(crate) type StepResult<S, E> = Result<S, (E, S)>;
+
+impl<S, P> StepHelper<S, P> {
+    pub fn new(state: S, place: P) -> Self {
+        let inner = StepHelper8(StepHelper9(StepHelper10(StepHelper11(StepHelper12(Some(
+            (state, place),
+        ))))));
+        StepHelper(StepHelper2(StepHelper3(StepHelper4(StepHelper5(
+            StepHelper6(StepHelper7(inner)),
+        )))))
+    }
+}
+
+impl<S, P> Deref for StepHelper<S, P> {
+    type Target = StepHelper2<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper2<S, P> {
+    type Target = StepHelper3<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper2<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper3<S, P> {
+    type Target = StepHelper4<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper3<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper4<S, P> {
+    type Target = StepHelper5<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper4<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper5<S, P> {
+    type Target = StepHelper6<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper5<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper6<S, P> {
+    type Target = StepHelper7<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper6<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper7<S, P> {
+    type Target = StepHelper8<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper7<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper8<S, P> {
+    type Target = StepHelper9<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper8<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper9<S, P> {
+    type Target = StepHelper10<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper9<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper10<S, P> {
+    type Target = StepHelper11<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper10<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> Deref for StepHelper11<S, P> {
+    type Target = StepHelper12<S, P>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S, P> DerefMut for StepHelper11<S, P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<S, P> StepHelper12<S, P> {
+    pub(crate) fn unwrap(&mut self) -> (S, P) {
+        self.0.take().expect("StepHelper used twice")
+    }
+}
+
+impl<T, P> StepHelper2<T, P>
+where
+    P: TryReplace<T>,
+{
+    pub fn step(&mut self) -> T {
+        let (value, place) = self.unwrap();
+        place.replace(value)
+    }
+
+    pub fn try_step(&mut self) -> StepResult<T, P::Error> {
+        let (value, place) = self.unwrap();
+        place.try_replace(value)
+    }
+}
+
+impl<T, P> StepHelper3<T, P>
+where
+    P: Replace<T>,
+{
+    pub fn step(&mut self) -> T {
+        let (value, place) = self.unwrap();
+        place.replace(value)
+    }
+
+    pub fn try_step(&mut self) -> StepResult<T, Infallible> {
+        Ok(self.step())
+    }
+}
+
+impl<'a, T> RotateHelper<&'a Cell<T>> {
+    pub fn take(&mut self) -> Held<&'a Cell<T>> {
+        Held(self.0 .0 .0 .0 .0 .0)
+    }
+
+    pub fn try_take(&mut self) -> Result<Held<&'a Cell<T>>, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Direct
+    }
+
+    pub fn take_no_clone(&mut self) -> Held<&'a Cell<T>> {
+        self.take()
+    }
+}
+
+impl<'a, T> RotateHelper<&'a RefCell<T>> {
+    pub fn take(&mut self) -> Held<&'a RefCell<T>> {
+        self.try_take().unwrap_or_else(|e| replace_failed(e))
+    }
+
+    pub fn try_take(&mut self) -> Result<Held<&'a RefCell<T>>, TryError> {
+        // Fail early, as if the value were taken out, if the cell is mutably borrowed.
+        let place = self.0 .0 .0 .0 .0 .0;
+        drop(place.try_borrow()?);
+        Ok(Held(place))
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Direct
+    }
+
+    pub fn take_no_clone(&mut self) -> Held<&'a RefCell<T>> {
+        self.take()
+    }
+}
+
+/// Swaps the value of a [`Cell`] with another place.
+pub(crate) trait SwapWithCell<T> {
+    fn swap_with_cell(self, x: &Cell<T>) -> Result<(), TryError>;
+}
+
+impl<T> SwapWithCell<T> for &mut T {
+    fn swap_with_cell(self, x: &Cell<T>) -> Result<(), TryError> {
+        x.swap(Cell::from_mut(self));
+        Ok(())
+    }
+}
+
+impl<T> SwapWithCell<T> for &Cell<T> {
+    fn swap_with_cell(self, x: &Cell<T>) -> Result<(), TryError> {
+        x.swap(self);
+        Ok(())
+    }
+}
+
+impl<T> SwapWithCell<T> for &RefCell<T> {
+    fn swap_with_cell(self, x: &Cell<T>) -> Result<(), TryError> {
+        let mut y = self.try_borrow_mut()?;
+        x.swap(Cell::from_mut(&mut *y));
+        Ok(())
+    }
+}
+
+/// Swaps the value of a [`RefCell`] with another place.
+pub(crate) trait SwapWithRefCell<T> {
+    fn swap_with_ref_cell(self, x: &RefCell<T>) -> Result<(), TryError>;
+}
+
+impl<T> SwapWithRefCell<T> for &mut T {
+    fn swap_with_ref_cell(self, x: &RefCell<T>) -> Result<(), TryError> {
+        let mut x = x.try_borrow_mut()?;
+        mem::swap(&mut *x, self);
+        Ok(())
+    }
+}
+
+impl<T> SwapWithRefCell<T> for &Cell<T> {
+    fn swap_with_ref_cell(self, x: &RefCell<T>) -> Result<(), TryError> {
+        let mut x = x.try_borrow_mut()?;
+        self.swap(Cell::from_mut(&mut *x));
+        Ok(())
+    }
+}
+
+impl<T> SwapWithRefCell<T> for &RefCell<T> {
+    fn swap_with_ref_cell(self, x: &RefCell<T>) -> Result<(), TryError> {
+        if ptr::eq(x, self) {
+            return Ok(());
+        }
+        let mut x = x.try_borrow_mut()?;
+        let mut y = self.try_borrow_mut()?;
+        mem::swap(&mut *x, &mut *y);
+        Ok(())
+    }
+}
+
+macro_rules! impl_held_cell {
+    ($held:ty, $method:ident; $($(#[$attr:meta])* $place:ty;)*) => {
+        $(
+            $(#[$attr])*
+            impl<'a, 'b, T> StepHelper<Held<$held>, $place> {
+                pub fn step(&mut self) -> Held<$held> {
+                    self.try_step().unwrap_or_else(|(e, _)| replace_failed(e))
+                }
+
+                pub fn try_step(&mut self) -> StepResult<Held<$held>, TryError> {
+                    let (held, place) = self.0.unwrap();
+                    match place.$method(held.0) {
+                        Ok(()) => Ok(held),
+                        Err(e) => Err((e, held)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_held_cell! {
+    &'a Cell<T>, swap_with_cell;
+    &'b mut T;
+    &'b Cell<T>;
+    &'b RefCell<T>;
+    #[cfg(feature = "std")]
+    &'b std::sync::Mutex<T>;
+    #[cfg(feature = "std")]
+    &'b std::sync::RwLock<T>;
+    #[cfg(feature = "std")]
+    crate::IgnorePoison<&'b std::sync::Mutex<T>>;
+    #[cfg(feature = "std")]
+    crate::IgnorePoison<&'b std::sync::RwLock<T>>;
+}
+
+impl_held_cell! {
+    &'a RefCell<T>, swap_with_ref_cell;
+    &'b mut T;
+    &'b Cell<T>;
+    &'b RefCell<T>;
+    #[cfg(feature = "std")]
+    &'b std::sync::Mutex<T>;
+    #[cfg(feature = "std")]
+    &'b std::sync::RwLock<T>;
+    #[cfg(feature = "std")]
+    crate::IgnorePoison<&'b std::sync::Mutex<T>>;
+    #[cfg(feature = "std")]
+    crate::IgnorePoison<&'b std::sync::RwLock<T>>;
+}
+
+/// Implements the levels from [`StepHelper4`] to [`StepHelper12`] for a [`Held`] place.
+///
+/// They take out the value as in [`take!`](crate::take!), step it through the other place,
+/// and then put the value given back to the held place.
+/// Other places, such as atomics or your own [`Replace`], can follow a cell or a lock this way.
+macro_rules! impl_held_fallback {
+    ($($held:ty, $takeable:ident $(, $clone:ident)?;)*) => {
+        $(
+            impl_held_fallback!(@try $held, StepHelper4, Copy);
+            impl_held_fallback!(@try $held, StepHelper5, Sentinel);
+            impl_held_fallback!(@try $held, StepHelper6, Default);
+            $(impl_held_fallback!(@try $held, StepHelper7, $clone);)?
+            impl_held_fallback!(@replace $held, StepHelper8, Copy);
+            impl_held_fallback!(@replace $held, StepHelper9, Sentinel);
+            impl_held_fallback!(@replace $held, StepHelper10, Default);
+            $(impl_held_fallback!(@replace $held, StepHelper11, $clone);)?
+
+            impl<'a, T, P> StepHelper12<Held<$held>, P>
+            where
+                P: Replace<T>,
+            {
+                pub fn step(&mut self) -> Held<$held>
+                where
+                    T: $takeable,
+                {
+                    unreachable!()
+                }
+
+                pub fn try_step(&mut self) -> StepResult<Held<$held>, TryError>
+                where
+                    T: $takeable,
+                {
+                    unreachable!()
+                }
+            }
+        )*
+    };
+    (@try $held:ty, $level:ident, $bound:ident) => {
+        impl<'a, T, P> $level<Held<$held>, P>
+        where
+            T: $bound,
+            P: TryReplace<T>,
+            P::Error: Into<TryError>,
+        {
+            pub fn step(&mut self) -> Held<$held> {
+                self.try_step().unwrap_or_else(|(e, _)| replace_failed(e))
+            }
+
+            pub fn try_step(&mut self) -> StepResult<Held<$held>, TryError> {
+                impl_held_fallback!(@body self, |place, value| {
+                    place.try_replace(value).map_err(|(e, value)| (e.into(), value))
+                })
+            }
+        }
+    };
+    (@replace $held:ty, $level:ident, $bound:ident) => {
+        impl<'a, T, P> $level<Held<$held>, P>
+        where
+            T: $bound,
+            P: Replace<T>,
+        {
+            pub fn step(&mut self) -> Held<$held> {
+                self.try_step().unwrap_or_else(|(e, _)| replace_failed(e))
+            }
+
+            pub fn try_step(&mut self) -> StepResult<Held<$held>, TryError> {
+                impl_held_fallback!(@body self, |place, value| Ok(place.replace(value)))
+            }
+        }
+    };
+    (@body $self:ident, |$place:ident, $value:ident| $replace:expr) => {{
+        let (held, $place) = $self.unwrap();
+        let $value = match TakeHelper::new(held.0).try_take() {
+            Ok(value) => value,
+            Err(e) => return Err((e, held)),
+        };
+        let result: Result<T, (TryError, T)> = $replace;
+        match result {
+            Ok(value) => match TryReplace::try_replace(held.0, value) {
+                Ok(_) => Ok(held),
+                Err((e, _)) => Err((e.into(), held)),
+            },
+            Err((e, value)) => {
+                // Put the value back, in case it was taken out leaving a sentinel.
+                let _ = TryReplace::try_replace(held.0, value);
+                Err((e, held))
+            }
+        }
+    }};
+}
+
+#[cfg(feature = "std")]
+pub(crate) use impl_held_fallback;
+
+impl_held_fallback! {
+    &'a Cell<T>, CellTakeable;
+    &'a RefCell<T>, Takeable, Clone;
+}
@@ -0,0 +1,137 @@
+/// The way [`take!`](crate::take!) and [`swap!`](crate::swap!) access a place.
+///
+/// It is returned by [`take_strategy!`](crate::take_strategy!) and [`swap_strategy!`](crate::swap_strategy!).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Strategy {
+    /// The value is copied out, as [`T: Copy`](std::marker::Copy).
+    Copy,
+    /// The value is swapped with the sentinel value, as [`T: Sentinel`](crate::Sentinel),
+    /// or with the sentinel given explicitly.
+    Sentinel,
+    /// The value is swapped with the default value, as [`T: Default`](std::default::Default).
+    Default,
+    /// The value is cloned out, as [`T: Clone`](std::clone::Clone).
+    Clone,
+    /// The places are swapped directly without a sentinel,
+    /// because the first place is a cell or a lock.
+    Direct,
+}
+
+/// Reports how [`take!`](crate::take!) accesses the place.
+///
+/// The argument is evaluated, but the place is not accessed.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use omniswap::Strategy;
+/// let mut v = vec![1, 2, 3];
+/// assert_eq!(omniswap::take_strategy!(&mut v), Strategy::Default);
+/// assert_eq!(v, [1, 2, 3]);
+/// ```
+#[macro_export]
+macro_rules! take_strategy {
+    ($p: expr) => {
+        $crate::TakeHelper::new($p).strategy()
+    };
+    ($p: expr,) => {
+        $crate::take_strategy!($p)
+    };
+}
+
+/// Reports how [`swap!`](crate::swap!) and [`rotate!`](crate::rotate!) access the places.
+///
+/// The strategy is determined by the first place.
+/// Only the first argument is evaluated; the places are not accessed.
+/// If the place syntax is used, the index expressions are evaluated as in [`swap!`](crate::swap!).
+///
+/// It accepts the same modifiers as [`swap!`](crate::swap!).
+/// With `@copy`, `@sentinel`, `@default` or `@clone`, it reports the pinned strategy,
+/// and with an explicit sentinel given by `with`, it reports [`Strategy::Sentinel`];
+/// in these cases, the first argument is not evaluated either.
+///
+/// If the first place is a cell or a lock, it reports [`Strategy::Direct`].
+/// In that case, if the value has to be passed to a place that cannot be swapped directly,
+/// such as an atomic, it is copied instead.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use omniswap::Strategy;
+/// #[derive(Clone)]
+/// struct Mesh(Vec<f32>);
+///
+/// let mut a = Mesh(vec![1.0]);
+/// let mut b = Mesh(vec![2.0]);
+/// assert_eq!(omniswap::swap_strategy!(&mut a, &mut b), Strategy::Clone);
+///
+/// let a = RefCell::new(Mesh(vec![1.0]));
+/// let b = RefCell::new(Mesh(vec![2.0]));
+/// assert_eq!(omniswap::swap_strategy!(&a, &b), Strategy::Direct);
+/// ```
+#[macro_export]
+macro_rules! swap_strategy {
+    (@copy $($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl (using take_copy Copy) rotate [] $($args)*)
+    };
+    (@sentinel $($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl (using take_sentinel Sentinel) rotate [] $($args)*)
+    };
+    (@default $($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl (using take_default Default) rotate [] $($args)*)
+    };
+    (@clone $($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl (using take_clone Clone) rotate [] $($args)*)
+    };
+    (with $sentinel: expr; $($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl (with ($sentinel)) rotate [] $($args)*)
+    };
+    ($($args: tt)*) => {
+        $crate::__place_args!(__strategy_impl panic rotate [] $($args)*)
+    };
+}
+
+/// Internal macro used in [`swap_strategy!`].
+///
+/// Please use [`swap_strategy!`] instead.
+///
+/// The arguments other than the first place are only type-checked.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __strategy_impl {
+    (@start panic rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            let strategy = $crate::RotateHelper::new($x).strategy();
+            if false {
+                $(
+                    let _ = $y;
+                )*
+            }
+            strategy
+        }
+    };
+    (@start (using $take: ident $strategy: ident) rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            if false {
+                let _ = $crate::$take($x);
+                $(
+                    let _ = $y;
+                )*
+            }
+            $crate::Strategy::$strategy
+        }
+    };
+    (@start (with ($sentinel: expr)) rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            if false {
+                let _ = $crate::Replace::replace($x, $sentinel);
+                $(
+                    let _ = $y;
+                )*
+            }
+            $crate::Strategy::Sentinel
+        }
+    };
+}
@@ -15,6 +15,26 @@
 /// assert_eq!((x, y), ('b', 'a'));
 /// ```
 ///
+/// ## Place syntax
+///
+/// Instead of a reference, an argument may be an indexed place such as `mut a[i][j]`,
+/// marked with `mut` and written without `&`.
+/// Each index expression is evaluated exactly once and bound to a temporary,
+/// and the place is projected again from these temporaries whenever it is accessed.
+///
+/// ```rust
+/// let mut a = vec![vec![1, 2], vec![3, 4]];
+/// let mut indices = [0, 1].into_iter();
+/// let mut next = || indices.next().unwrap();
+/// // `next()` is called only once, even though the first place is accessed twice.
+/// omniswap::swap!(mut a[next()][0], mut a[1][next()]);
+/// assert_eq!(a, [[4, 2], [3, 1]]);
+/// ```
+///
+/// The base must be a variable; the place syntax can be mixed with ordinary references.
+/// Without the `mut` marker, `a[i]` is an ordinary expression:
+/// with `cells: [&Cell<T>; 2]`, `swap!(cells[0], cells[1])` swaps the contents of the cells.
+///
 /// ## Swapping with cells
 ///
 /// It also supports [`std::cell::Cell`] and [`std::cell::RefCell`].
@@ -38,26 +58,134 @@
 /// assert_eq!((x, y.get()), ('b', 'a'));
 /// ```
 ///
+/// If the first place is a cell, it does not need a sentinel.
+/// Instead, it swaps the values directly, as in [`Cell::swap`](std::cell::Cell::swap)
+/// and [`RefCell::swap`](std::cell::RefCell::swap), so it works for any `T`.
+///
+/// ```rust
+/// # use std::cell::Cell;
+/// let x: Cell<Box<dyn Fn() -> i32>> = Cell::new(Box::new(|| 1));
+/// let mut y: Box<dyn Fn() -> i32> = Box::new(|| 2);
+/// omniswap::swap!(&x, &mut y);
+/// assert_eq!((x.into_inner()(), y()), (2, 1));
+/// ```
+///
+/// When the other place is not a cell, `&mut T` or a lock, for example an atomic,
+/// the value of the first place is taken out as in [`take!`](crate::take!) instead,
+/// so the [requirements](#sentinel-requirements) below apply.
+///
+/// The places other than the first one may also be of your own type implementing
+/// [`Replace`](crate::Replace).
+/// Implement [`TryReplace`](crate::TryReplace) as well
+/// so that [`try_swap!`](crate::try_swap!) reports the failures as errors.
+///
+/// ## Swapping locks
+///
+/// With the `std` feature, it also supports [`&Mutex<T>`](std::sync::Mutex)
+/// and [`&RwLock<T>`](std::sync::RwLock).
+///
+/// If the first place is a lock, it does not need a sentinel.
+/// Instead, it locks the two places and swaps the values directly,
+/// so it works for any `T`.
+/// Two locks are acquired in the order of their addresses, so that two threads swapping
+/// the same pair in opposite directions never deadlock.
+/// Swapping a lock with itself does nothing.
+///
+/// ```rust
+/// # #[cfg(feature = "std")] {
+/// # use std::sync::Mutex;
+/// let x: Mutex<Box<dyn Fn() -> i32>> = Mutex::new(Box::new(|| 1));
+/// let y: Mutex<Box<dyn Fn() -> i32>> = Mutex::new(Box::new(|| 2));
+/// omniswap::swap!(&x, &y);
+/// assert_eq!((x.lock().unwrap()(), y.lock().unwrap()()), (2, 1));
+/// # }
+/// ```
+///
+/// ## Swapping atomics
+///
+/// It also supports the types in [`core::sync::atomic`], such as
+/// [`&AtomicUsize`](core::sync::atomic::AtomicUsize) and [`&AtomicBool`](core::sync::atomic::AtomicBool).
+/// By default they are accessed with [`Ordering::SeqCst`](core::sync::atomic::Ordering::SeqCst).
+/// You can specify another ordering after a semicolon; in that case,
+/// all the places must implement [`ReplaceOrdered`](crate::ReplaceOrdered).
+///
+/// ```rust
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// let x = AtomicUsize::new(1);
+/// let y = AtomicUsize::new(2);
+/// omniswap::swap!(&x, &y; Ordering::AcqRel);
+/// assert_eq!((x.into_inner(), y.into_inner()), (2, 1));
+/// ```
+///
+/// Note that **the swap as a whole is not a single atomic operation**.
+/// It consists of a load from the first place followed by an atomic swap on each place,
+/// and other threads may observe or modify the places in between.
+/// If you need to swap two values atomically, put them behind a lock instead.
+///
+/// The ordering is evaluated before the places.
+///
+/// ## Explicit sentinel
+///
+/// You can give the sentinel value explicitly with `with`.
+/// In that case, the value type need not satisfy any trait,
+/// and the sentinel is returned after the swap so that it can be reused.
+///
+/// ```rust
+/// let mut a = [vec![1], vec![2]];
+/// let spare = Vec::new();
+/// let spare = omniswap::swap!(with spare; mut a[0], mut a[1]);
+/// assert_eq!(a, [[2], [1]]);
+/// assert!(spare.is_empty());
+/// ```
+///
+/// It takes out the value of the first place with [`take_or!`](crate::take_or!),
+/// even if the first place is a cell or a lock.
+/// The sentinel is evaluated before the places.
+///
 /// ## Sentinel requirements
 ///
 /// In order for `swap!` to work this way, it requires the value type
-/// to satisfy [`Default`](std::default::Default) or [`Clone`](std::clone::Clone).
+/// to satisfy [`Sentinel`](crate::Sentinel), [`Default`](std::default::Default) or [`Clone`](std::clone::Clone).
 ///
 /// If multiple traits can apply, the behavior is determined
 /// in the following order:
 ///
 /// 1. If [`T: Copy`](std::marker::Copy) it copies the value from the first seference.
-/// 2. Otherwise, if [`T: Default`](std::default::Default), it swaps the value with the default value.
-/// 3. Otherwise, if [`T: Clone`](std::clone::Clone), it clones out the value from the first reference.
+/// 2. Otherwise, if [`T: Sentinel`](crate::Sentinel), it swaps the value with the sentinel value.
+/// 3. Otherwise, if [`T: Default`](std::default::Default), it swaps the value with the default value.
+/// 4. Otherwise, if [`T: Clone`](std::clone::Clone), it clones out the value from the first reference.
+///
+/// These requirements do not apply if the first place is a cell or a lock
+/// and the other place is a cell, a lock or `&mut T`; see above.
 ///
-/// Additionally, if the first reference is [`&Cell<T>`](std::cell::Cell), `T` must satisfy
-/// [`Default`](std::default::Default) or [`Copy`](std::marker::Copy). [`Clone`](std::clone::Clone) alone does not suffice.
+/// To pin the strategy instead, put a modifier before the places:
+/// `@copy`, `@sentinel`, `@default` or `@clone`.
+/// It fails to compile if the value type does not support the strategy.
+/// The first place is accessed with the strategy even if it is a cell or a lock.
+///
+/// ```rust
+/// let mut a = [[0u8; 4096], [1u8; 4096]];
+/// // Swaps with a zeroed array instead of copying out 4 KiB.
+/// omniswap::swap!(@sentinel mut a[0], mut a[1]);
+/// assert_eq!((a[0][0], a[1][0]), (1, 0));
+/// ```
 ///
 /// ## Evaluation order
 ///
 /// It evaluates the arguments in the order of appearance, and then
 /// **the first argument again** to put the value back.
 ///
+/// If the place syntax is used, the index expressions are evaluated first,
+/// in the order of appearance.
+/// Then the places are projected in the order above, from the already evaluated indices.
+///
+/// ## Panics
+///
+/// If the second place is unavailable, for example because the
+/// [`RefCell`](std::cell::RefCell) is already borrowed,
+/// it puts the value back to the first place and then panics.
+/// See [`rotate!`](crate::rotate!) for details.
+///
 /// ## Alternatives
 ///
 /// If the type does not have a good sentinel, you may need to use the following alternatives
@@ -69,11 +197,29 @@
 /// - [`RefCell::swap`](std::cell::RefCell::swap)
 #[macro_export]
 macro_rules! swap {
-    ($x: expr, $y: expr) => {
-        $crate::rotate!($x, $y)
+    (@copy $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_copy) swap [] $($args)*)
+    };
+    (@sentinel $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_sentinel) swap [] $($args)*)
     };
-    ($x: expr, $y: expr,) => {
-        $crate::rotate!($x, $y)
+    (@default $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_default) swap [] $($args)*)
+    };
+    (@clone $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_clone) swap [] $($args)*)
+    };
+    (with $sentinel: expr; $($args: tt)*) => {
+        {
+            let sentinel = $sentinel;
+            $crate::__place_args!(__rotate_impl (with sentinel) swap [] $($args)*)
+        }
+    };
+    ($x: expr, $y: expr; $order: expr) => {
+        $crate::rotate!($x, $y; $order)
+    };
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl panic swap [] $($args)*)
     };
 }
 
@@ -88,21 +234,439 @@ macro_rules! swap {
 /// omniswap::rotate!(&mut x, &mut y, &mut z);
 /// assert_eq!((x, y, z), ('c', 'a', 'b'));
 /// ```
+///
+/// ## Panics
+///
+/// If one of the places turns out to be unavailable in the middle,
+/// for example because the [`RefCell`](std::cell::RefCell) is already borrowed,
+/// it puts the original values back to the places already updated and then panics.
+/// To do so, it evaluates these arguments again, in the reverse order.
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use std::panic::{catch_unwind, AssertUnwindSafe};
+/// let x = RefCell::new(String::from("x"));
+/// let y = RefCell::new(String::from("y"));
+/// let z = RefCell::new(String::from("z"));
+/// let guard = z.borrow();
+/// let result = catch_unwind(AssertUnwindSafe(|| omniswap::rotate!(&x, &y, &z)));
+/// assert!(result.is_err());
+/// drop(guard);
+/// assert_eq!([x, y, z].map(RefCell::into_inner), ["x", "y", "z"]);
+/// ```
+///
+/// The place that turned out to be unavailable is not evaluated again.
+/// If it is the first place, when the last value is put back into it,
+/// the value is dropped instead and the other places keep their new values.
+/// Therefore, the last argument is evaluated at most once,
+/// and may be a `&mut` binding moved in, as in `swap!(&mut x, y)`.
+///
+/// This does not apply to panics raised while evaluating the arguments themselves,
+/// for example an index out of bounds.
+/// The arguments are evaluated in place, so that `?` and `return` in them work as usual,
+/// and nothing can be put back while unwinding:
+/// the value being moved is dropped, and the first place is left with the sentinel,
+/// or, if it is a cell or a lock, the swaps done so far are kept.
+///
+/// ```rust
+/// # use std::panic::{catch_unwind, AssertUnwindSafe};
+/// let mut x = String::from("x");
+/// let mut v = vec![String::from("y")];
+/// let result = catch_unwind(AssertUnwindSafe(|| omniswap::swap!(&mut x, &mut v[1])));
+/// assert!(result.is_err());
+/// assert_eq!((x.as_str(), v[0].as_str()), ("", "y"));
+/// ```
+///
+/// ## Atomics
+///
+/// Like [`swap!`], it accepts a memory ordering after a semicolon.
+///
+/// ```rust
+/// # use std::sync::atomic::{AtomicU8, Ordering};
+/// let a = [AtomicU8::new(1), AtomicU8::new(2), AtomicU8::new(3)];
+/// omniswap::rotate!(&a[0], &a[1], &a[2]; Ordering::AcqRel);
+/// assert_eq!(a.map(AtomicU8::into_inner), [3, 1, 2]);
+/// ```
+///
+/// As with [`swap!`], the rotation as a whole is **not** a single atomic operation.
+///
+/// ## Pinning the strategy
+///
+/// Like [`swap!`], it accepts a modifier that pins the strategy:
+/// `@copy`, `@sentinel`, `@default` or `@clone`.
+///
+/// ```rust
+/// # use std::rc::Rc;
+/// let mut a = [Rc::new(1), Rc::new(2), Rc::new(3)];
+/// omniswap::rotate!(@clone mut a[0], mut a[1], mut a[2]);
+/// assert_eq!(a.map(|x| *x), [3, 1, 2]);
+/// ```
+///
+/// ## Rotating by more than one position
+///
+/// With a number of positions `k` before a semicolon,
+/// it moves each value `k` positions to the right, wrapping around.
+/// See also [`rotate_left!`](crate::rotate_left!) and [`rotate_right!`](crate::rotate_right!).
+///
+/// ```rust
+/// let mut a = [1, 2, 3, 4, 5, 6];
+/// omniswap::rotate!(2; mut a[0], mut a[1], mut a[2], mut a[3], mut a[4], mut a[5]);
+/// assert_eq!(a, [5, 6, 1, 2, 3, 4]);
+/// ```
+///
+/// It is done in a single pass: the rotation is decomposed into `gcd(n, k)` cycles,
+/// and each cycle takes out only one value, as in `rotate!` without `k`.
+/// Unlike `rotate!` without `k`, it always takes out the value with [`take!`](crate::take!),
+/// even if the place is a cell or a lock.
+/// `k` is evaluated first, and if `k` is a multiple of the number of places,
+/// the places are not evaluated at all.
+/// Otherwise, the places are evaluated in the order they are accessed:
+/// for each cycle starting with the `i`-th place, the `i`-th, `i + k`-th, `i + 2k`-th places
+/// and so on, up to the `i`-th place again.
+/// On failure, all the values are put back, in the reverse order.
+/// Unlike `rotate!` without `k`, this is also done if evaluating an argument panics,
+/// by evaluating the other arguments again while unwinding.
+///
+/// Since the order depends on `k`, the arguments are evaluated inside a closure.
+/// So they cannot use `?`, `return`, `break`, `continue` or `.await`,
+/// and a `&mut` binding must be reborrowed as in `&mut *b`.
+/// The index expressions of the place syntax, such as `i?` in `mut a[i?]`,
+/// are evaluated beforehand, outside of the closure.
+///
+/// ## Explicit sentinel
+///
+/// Like [`swap!`], it accepts an explicit sentinel with `with`,
+/// and returns the leftover sentinel.
+///
+/// ```rust
+/// let mut x = String::from("x");
+/// let mut y = String::from("y");
+/// let mut z = String::from("z");
+/// let mut buf = String::with_capacity(64);
+/// buf = omniswap::rotate!(with buf; &mut x, &mut y, &mut z);
+/// assert_eq!((x.as_str(), y.as_str(), z.as_str()), ("z", "x", "y"));
+/// assert_eq!(buf.capacity(), 64);
+/// ```
+///
+/// On failure, the original values are put back as usual and the sentinel is dropped.
+///
+/// ## Cells and locks
+///
+/// If the first place is a cell, a [`&Mutex<T>`](std::sync::Mutex) or a [`&RwLock<T>`](std::sync::RwLock),
+/// the rotation is performed as a series of swaps between the first place and each of the others.
+/// See [`swap!`] for how each swap works.
+/// For locks, at most two of them are held at a time, so the rotation as a whole is **not** atomic:
+/// other threads may observe the intermediate states.
 #[macro_export]
 macro_rules! rotate {
-    ($x: expr, $($y: expr),*) => {
+    (@copy $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_copy) rotate [] $($args)*)
+    };
+    (@sentinel $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_sentinel) rotate [] $($args)*)
+    };
+    (@default $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_default) rotate [] $($args)*)
+    };
+    (@clone $($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl (using take_clone) rotate [] $($args)*)
+    };
+    (with $sentinel: expr; $($args: tt)*) => {
         {
-            let value = $crate::take!($x);
+            let sentinel = $sentinel;
+            $crate::__place_args!(__rotate_impl (with sentinel) rotate [] $($args)*)
+        }
+    };
+    ($x: expr, $($y: expr),*; $order: expr) => {
+        {
+            let order: ::core::sync::atomic::Ordering = $order;
+            let value = $crate::ReplaceOrdered::take_ordered($x, order);
             $(
-                let value = $crate::Replace::replace($y, value);
+                let value = $crate::ReplaceOrdered::replace_ordered($y, value, order);
             )*
-            let _ = $crate::Replace::replace($x, value);
+            let _ = $crate::ReplaceOrdered::replace_ordered($x, value, order);
+        }
+    };
+    ($k: expr; $($args: tt)*) => {
+        {
+            let k: usize = $k;
+            $crate::__place_args!(__rotate_by_impl (by panic k) rotate [] $($args)*)
+        }
+    };
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl panic rotate [] $($args)*)
+    };
+}
+
+/// Internal macro used in [`swap!`], [`rotate!`] and the other macros taking places.
+///
+/// Please use [`swap!`] or [`rotate!`] instead.
+///
+/// It parses the places, binding the index expressions of the place syntax to temporaries,
+/// and passes the places to `$crate::$callback!(@start $mode $arity [...])`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __place_args {
+    (@place $cb: ident $mode: tt $arity: ident [$($out: expr),*] $base: ident [$($b: ident)*] [$i: expr] $($rest: tt)*) => {
+        {
+            let i = $i;
+            $crate::__place_args!(@place $cb $mode $arity [$($out),*] $base [$($b)* i] $($rest)*)
         }
     };
-    ($x: expr) => {
-        $crate::rotate!($x,)
+    (@place $cb: ident $mode: tt $arity: ident [$($out: expr),*] $base: ident [$($b: ident)*]; $($rest: tt)*) => {
+        $crate::__place_args!($cb $mode $arity [$($out,)* &mut $base $([$b])*] $($rest)*)
+    };
+    ($cb: ident $mode: tt $arity: ident [$($out: expr),*]) => {
+        $crate::$cb!(@start $mode $arity [$($out),*])
+    };
+    ($cb: ident $mode: tt $arity: ident [$($out: expr),*] mut $base: ident $([$($i: tt)*])+ $(, $($rest: tt)*)?) => {
+        $crate::__place_args!(@place $cb $mode $arity [$($out),*] $base [] $([$($i)*])+; $($($rest)*)?)
+    };
+    ($cb: ident $mode: tt $arity: ident [$($out: expr),*] $x: expr $(, $($rest: tt)*)?) => {
+        $crate::__place_args!($cb $mode $arity [$($out,)* $x] $($($rest)*)?)
+    };
+}
+
+/// Internal macro used in [`swap!`] and [`rotate!`].
+///
+/// Please use [`swap!`] or [`rotate!`] instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __rotate_impl {
+    (@step $mode: ident [$x: expr] [$($done: expr),*] $value: ident;) => {
+        match $crate::StepHelper::new($value, $x).try_step() {
+            ::core::result::Result::Ok($value) => $crate::__rotate_impl!(@done $mode $value),
+            ::core::result::Result::Err((error, $value)) => {
+                // The other places are not evaluated again, so that the last argument
+                // is evaluated at most once and may be a `&mut` binding moved in.
+                let _ = $value;
+                $crate::__rotate_impl!(@fail $mode error)
+            }
+        }
+    };
+    (@step $mode: ident [$x: expr] [$($done: expr),*] $value: ident; $y: expr $(, $rest: expr)*) => {
+        match $crate::StepHelper::new($value, $y).try_step() {
+            ::core::result::Result::Ok($value) => {
+                $crate::__rotate_impl!(@step $mode [$x] [$y $(, $done)*] $value; $($rest),*)
+            }
+            ::core::result::Result::Err((error, $value)) => {
+                $crate::__rotate_impl!(@restore [$x] [$($done),*] $value);
+                $crate::__rotate_impl!(@fail $mode error)
+            }
+        }
+    };
+    (@restore [$x: expr] [$($done: expr),*] $value: ident) => {
+        {
+            $(
+                let $value = $crate::StepHelper::new($value, $done).step();
+            )*
+            // If the first place is still unavailable, it has not been updated
+            // unless the arguments themselves borrowed it in the meantime.
+            let _ = $crate::StepHelper::new($value, $x).try_step();
+        }
+    };
+    (@done panic $value: ident) => {
+        {
+            let _ = $value;
+        }
+    };
+    (@done try $value: ident) => {
+        {
+            let _ = $value;
+            ::core::result::Result::<(), $crate::TryError>::Ok(())
+        }
+    };
+    (@done with $value: ident) => {
+        $value
+    };
+    (@fail panic $error: ident) => {
+        $crate::replace_failed($error)
+    };
+    (@fail with $error: ident) => {
+        $crate::replace_failed($error)
+    };
+    (@fail try $error: ident) => {
+        ::core::result::Result::Err(::core::convert::From::from($error))
+    };
+    (@start $mode: tt swap [$x: expr, $y: expr]) => {
+        $crate::__rotate_impl!(@start $mode rotate [$x, $y])
+    };
+    (@start $mode: tt swap [$($out: expr),*]) => {
+        ::core::compile_error!("expected exactly two places")
+    };
+    (@start $mode: tt rotate_left [$x: expr $(, $y: expr)*]) => {
+        $crate::__rotate_impl!(@rev $mode [$x] [] $($y),*)
+    };
+    (@rev $mode: tt [$x: expr] [$($r: expr),*] $y: expr $(, $rest: expr)*) => {
+        $crate::__rotate_impl!(@rev $mode [$x] [$y $(, $r)*] $($rest),*)
+    };
+    (@rev $mode: tt [$x: expr] [$($r: expr),*]) => {
+        $crate::__rotate_impl!(@start $mode rotate [$x $(, $r)*])
+    };
+    (@start panic rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            let value = $crate::RotateHelper::new($x).take();
+            $crate::__rotate_impl!(@step panic [$x] [] value; $($y),*)
+        }
+    };
+    (@start no_clone rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            let value = $crate::RotateHelper::new($x).take_no_clone();
+            $crate::__rotate_impl!(@step panic [$x] [] value; $($y),*)
+        }
+    };
+    (@start (with $sentinel: ident) rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            let value = $crate::Replace::replace($x, $sentinel);
+            $crate::__rotate_impl!(@step with [$x] [] value; $($y),*)
+        }
+    };
+    (@start (using $take: ident) rotate [$x: expr $(, $y: expr)*]) => {
+        {
+            let value = $crate::$take($x);
+            $crate::__rotate_impl!(@step panic [$x] [] value; $($y),*)
+        }
+    };
+    (@start try rotate [$x: expr $(, $y: expr)*]) => {
+        match $crate::RotateHelper::new($x).try_take() {
+            ::core::result::Result::Ok(value) => {
+                $crate::__rotate_impl!(@step try [$x] [] value; $($y),*)
+            }
+            ::core::result::Result::Err(error) => ::core::result::Result::Err(error),
+        }
+    };
+}
+
+/// A fallible variant of [`swap!`].
+///
+/// It returns [`Err`] instead of panicking if a place is unavailable,
+/// for example because the [`RefCell`](std::cell::RefCell) is already borrowed.
+/// Every place holds its original value in that case.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// let x = RefCell::new(String::from("x"));
+/// let y = RefCell::new(String::from("y"));
+///
+/// let guard = y.borrow();
+/// assert!(omniswap::try_swap!(&x, &y).is_err());
+/// drop(guard);
+/// assert_eq!((x.borrow().as_str(), y.borrow().as_str()), ("x", "y"));
+///
+/// assert!(omniswap::try_swap!(&x, &y).is_ok());
+/// assert_eq!((x.borrow().as_str(), y.borrow().as_str()), ("y", "x"));
+/// ```
+///
+/// The requirements and the evaluation order are the same as [`swap!`].
+/// See [`try_rotate!`](crate::try_rotate!) for the evaluation order on failure.
+#[macro_export]
+macro_rules! try_swap {
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl try swap [] $($args)*)
+    };
+}
+
+/// A fallible variant of [`rotate!`](crate::rotate!).
+///
+/// It returns [`Err`] instead of panicking if a place is unavailable,
+/// for example because the [`RefCell`](std::cell::RefCell) is already borrowed.
+/// Every place holds its original value in that case.
+///
+/// ## Example
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// let x = RefCell::new(String::from("x"));
+/// let y = RefCell::new(String::from("y"));
+/// let z = RefCell::new(String::from("z"));
+///
+/// let guard = z.borrow_mut();
+/// assert!(omniswap::try_rotate!(&x, &y, &z).is_err());
+/// drop(guard);
+///
+/// assert!(omniswap::try_rotate!(&x, &y, &z).is_ok());
+/// assert_eq!([x, y, z].map(|c| c.into_inner()), ["z", "x", "y"]);
+/// ```
+///
+/// ## Evaluation order
+///
+/// It evaluates the arguments in the same order as [`rotate!`](crate::rotate!).
+///
+/// If a place is unavailable, it puts the original values back
+/// to the places already updated. To do so, it evaluates these arguments
+/// again, in the reverse order. If putting a value back fails, it panics.
+#[macro_export]
+macro_rules! try_rotate {
+    ($k: expr; $($args: tt)*) => {
+        {
+            let k: usize = $k;
+            $crate::__place_args!(__rotate_by_impl (by try k) rotate [] $($args)*)
+        }
+    };
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl try rotate [] $($args)*)
+    };
+}
+
+/// An alias of [`rotate!`](crate::rotate!) that makes the direction explicit.
+///
+/// It moves each value one position to the right, and the last one to the first place.
+///
+/// ## Example
+///
+/// ```rust
+/// let mut x = 'a';
+/// let mut y = 'b';
+/// let mut z = 'c';
+/// omniswap::rotate_right!(&mut x, &mut y, &mut z);
+/// assert_eq!((x, y, z), ('c', 'a', 'b'));
+/// ```
+///
+/// It also accepts the number of positions as in `rotate!(k; ...)`.
+#[macro_export]
+macro_rules! rotate_right {
+    ($($args: tt)*) => {
+        $crate::rotate!($($args)*)
+    };
+}
+
+/// A variant of [`rotate!`](crate::rotate!) that moves the values in the opposite direction.
+///
+/// It moves each value one position to the left, and the first one to the last place.
+///
+/// ## Example
+///
+/// ```rust
+/// let mut x = 'a';
+/// let mut y = 'b';
+/// let mut z = 'c';
+/// omniswap::rotate_left!(&mut x, &mut y, &mut z);
+/// assert_eq!((x, y, z), ('b', 'c', 'a'));
+///
+/// let mut a = [1, 2, 3, 4, 5];
+/// omniswap::rotate_left!(2; mut a[0], mut a[1], mut a[2], mut a[3], mut a[4]);
+/// assert_eq!(a, [3, 4, 5, 1, 2]);
+/// ```
+///
+/// `rotate_left!(x, y, z)` works exactly like `rotate!(x, z, y)`,
+/// so the requirements and the behavior on failure are the same as [`rotate!`](crate::rotate!).
+///
+/// ## Evaluation order
+///
+/// It evaluates the first argument, the other arguments in the **reverse** order,
+/// and then the first argument again.
+/// With the number of positions, the order is the same as `rotate!(k; ...)`.
+#[macro_export]
+macro_rules! rotate_left {
+    ($k: expr; $($args: tt)*) => {
+        {
+            let k: usize = $k;
+            $crate::__place_args!(__rotate_by_impl (by panic k) rotate_left [] $($args)*)
+        }
     };
-    ($x: expr, $($y: expr),*,) => {
-        $crate::rotate!($x, $($y),*)
+    ($($args: tt)*) => {
+        $crate::__place_args!(__rotate_impl panic rotate_left [] $($args)*)
     };
 }
@@ -0,0 +1,475 @@
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::step::{impl_held_fallback, StepResult, SwapWithCell, SwapWithRefCell};
+use crate::{
+    replace_failed, CloneOut, CloneRejected, CopyOut, Held, Movable, Reborrow, Replace,
+    RotateHelper, Sentinel, StepHelper, StepHelper10, StepHelper11, StepHelper12, StepHelper4,
+    StepHelper5, StepHelper6, StepHelper7, StepHelper8, StepHelper9, Strategy, TakeHelper,
+    TakeHelper2, TakeHelper3, TakeHelper4, TakeHelper5, Takeable, TryError, TryReplace,
+};
+
+/// A place wrapper that ignores lock poisoning.
+///
+/// By default, [`&Mutex<T>`](std::sync::Mutex) and [`&RwLock<T>`](std::sync::RwLock)
+/// places panic if the lock is poisoned, and the `try_` variants such as
+/// [`try_swap!`](crate::try_swap!) return [`TryError::Poisoned`].
+/// Wrapping the place in `IgnorePoison` makes them access the value anyway,
+/// as in [`PoisonError::into_inner`](std::sync::PoisonError::into_inner).
+///
+/// This requires the `std` feature.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::sync::Mutex;
+/// # use omniswap::IgnorePoison;
+/// let x = Mutex::new(1);
+/// let y = Mutex::new(2);
+/// let _ = std::panic::catch_unwind(|| {
+///     let _guard = x.lock().unwrap();
+///     panic!();
+/// });
+/// assert!(x.is_poisoned());
+///
+/// omniswap::swap!(IgnorePoison(&x), IgnorePoison(&y));
+/// assert_eq!(*y.lock().unwrap(), 1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IgnorePoison<P>(pub P);
+
+impl<'a, T: ?Sized> Reborrow for IgnorePoison<&'a T> {
+    type Target<'b>
+        = IgnorePoison<&'a T>
+    where
+        Self: 'b;
+    fn reborrow(&mut self) -> IgnorePoison<&'a T> {
+        *self
+    }
+}
+
+/// Lock operations with the poisoning policy applied.
+trait Lock: Copy {
+    type Value;
+    type ReadGuard: Deref<Target = Self::Value>;
+    type WriteGuard: DerefMut<Target = Self::Value>;
+    fn read_lock(self) -> Result<Self::ReadGuard, TryError>;
+    fn write_lock(self) -> Result<Self::WriteGuard, TryError>;
+    /// The address used to order the locks.
+    fn addr(self) -> *const ();
+}
+
+impl<'a, T> Lock for &'a Mutex<T> {
+    type Value = T;
+    type ReadGuard = MutexGuard<'a, T>;
+    type WriteGuard = MutexGuard<'a, T>;
+    fn read_lock(self) -> Result<Self::ReadGuard, TryError> {
+        Ok(self.lock()?)
+    }
+    fn write_lock(self) -> Result<Self::WriteGuard, TryError> {
+        Ok(self.lock()?)
+    }
+    fn addr(self) -> *const () {
+        self as *const Mutex<T> as *const ()
+    }
+}
+
+impl<'a, T> Lock for &'a RwLock<T> {
+    type Value = T;
+    type ReadGuard = RwLockReadGuard<'a, T>;
+    type WriteGuard = RwLockWriteGuard<'a, T>;
+    fn read_lock(self) -> Result<Self::ReadGuard, TryError> {
+        Ok(self.read()?)
+    }
+    fn write_lock(self) -> Result<Self::WriteGuard, TryError> {
+        Ok(self.write()?)
+    }
+    fn addr(self) -> *const () {
+        self as *const RwLock<T> as *const ()
+    }
+}
+
+impl<'a, T> Lock for IgnorePoison<&'a Mutex<T>> {
+    type Value = T;
+    type ReadGuard = MutexGuard<'a, T>;
+    type WriteGuard = MutexGuard<'a, T>;
+    fn read_lock(self) -> Result<Self::ReadGuard, TryError> {
+        Ok(self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+    fn write_lock(self) -> Result<Self::WriteGuard, TryError> {
+        Ok(self.0.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+    fn addr(self) -> *const () {
+        self.0.addr()
+    }
+}
+
+impl<'a, T> Lock for IgnorePoison<&'a RwLock<T>> {
+    type Value = T;
+    type ReadGuard = RwLockReadGuard<'a, T>;
+    type WriteGuard = RwLockWriteGuard<'a, T>;
+    fn read_lock(self) -> Result<Self::ReadGuard, TryError> {
+        Ok(self.0.read().unwrap_or_else(PoisonError::into_inner))
+    }
+    fn write_lock(self) -> Result<Self::WriteGuard, TryError> {
+        Ok(self.0.write().unwrap_or_else(PoisonError::into_inner))
+    }
+    fn addr(self) -> *const () {
+        self.0.addr()
+    }
+}
+
+/// Swaps the values of two locks.
+///
+/// The locks are acquired in the order of their addresses,
+/// so that two threads swapping the same pair never deadlock.
+fn swap_locks<X, Y>(x: X, y: Y) -> Result<(), TryError>
+where
+    X: Lock,
+    Y: Lock<Value = X::Value>,
+{
+    if x.addr() == y.addr() {
+        return Ok(());
+    }
+    if x.addr() < y.addr() {
+        let mut x = x.write_lock()?;
+        let mut y = y.write_lock()?;
+        mem::swap(&mut *x, &mut *y);
+    } else {
+        let mut y = y.write_lock()?;
+        let mut x = x.write_lock()?;
+        mem::swap(&mut *x, &mut *y);
+    }
+    Ok(())
+}
+
+/// Swaps the value of a lock with another place.
+trait SwapWithLock<X: Lock> {
+    fn swap_with_lock(self, x: X) -> Result<(), TryError>;
+}
+
+macro_rules! impl_swap_with_lock {
+    ($($place:ty;)*) => {
+        $(
+            impl<'b, X: Lock> SwapWithLock<X> for $place {
+                fn swap_with_lock(self, x: X) -> Result<(), TryError> {
+                    swap_locks(x, self)
+                }
+            }
+        )*
+    };
+}
+
+impl_swap_with_lock! {
+    &'b Mutex<X::Value>;
+    &'b RwLock<X::Value>;
+    IgnorePoison<&'b Mutex<X::Value>>;
+    IgnorePoison<&'b RwLock<X::Value>>;
+}
+
+impl<X: Lock> SwapWithLock<X> for &mut X::Value {
+    fn swap_with_lock(self, x: X) -> Result<(), TryError> {
+        let mut x = x.write_lock()?;
+        mem::swap(&mut *x, self);
+        Ok(())
+    }
+}
+
+impl<X: Lock> SwapWithLock<X> for &Cell<X::Value> {
+    fn swap_with_lock(self, x: X) -> Result<(), TryError> {
+        let mut x = x.write_lock()?;
+        Cell::from_mut(&mut *x).swap(self);
+        Ok(())
+    }
+}
+
+impl<X: Lock> SwapWithLock<X> for &RefCell<X::Value> {
+    fn swap_with_lock(self, x: X) -> Result<(), TryError> {
+        let mut x = x.write_lock()?;
+        let mut y = self.try_borrow_mut()?;
+        mem::swap(&mut *x, &mut *y);
+        Ok(())
+    }
+}
+
+macro_rules! impl_swap_with_cell {
+    ($($place:ty;)*) => {
+        $(
+            impl<'b, T> SwapWithCell<T> for $place {
+                fn swap_with_cell(self, x: &Cell<T>) -> Result<(), TryError> {
+                    let mut y = self.write_lock()?;
+                    x.swap(Cell::from_mut(&mut *y));
+                    Ok(())
+                }
+            }
+
+            impl<'b, T> SwapWithRefCell<T> for $place {
+                fn swap_with_ref_cell(self, x: &RefCell<T>) -> Result<(), TryError> {
+                    let mut x = x.try_borrow_mut()?;
+                    let mut y = self.write_lock()?;
+                    mem::swap(&mut *x, &mut *y);
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_swap_with_cell! {
+    &'b Mutex<T>;
+    &'b RwLock<T>;
+    IgnorePoison<&'b Mutex<T>>;
+    IgnorePoison<&'b RwLock<T>>;
+}
+
+macro_rules! impl_held_lock {
+    ($($held:ty;)*) => {
+        $(
+            impl<'a, T> RotateHelper<$held> {
+                pub fn take(&mut self) -> Held<$held> {
+                    Held(self.0 .0 .0 .0 .0 .0)
+                }
+
+                pub fn try_take(&mut self) -> Result<Held<$held>, TryError> {
+                    Ok(self.take())
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Direct
+                }
+
+                pub fn take_no_clone(&mut self) -> Held<$held> {
+                    self.take()
+                }
+            }
+
+            impl_held_lock!(@step $held;
+                &'b mut T;
+                &'b Cell<T>;
+                &'b RefCell<T>;
+                &'b Mutex<T>;
+                &'b RwLock<T>;
+                IgnorePoison<&'b Mutex<T>>;
+                IgnorePoison<&'b RwLock<T>>;
+            );
+        )*
+    };
+    (@step $held:ty; $($place:ty;)*) => {
+        $(
+            impl<'a, 'b, T> StepHelper<Held<$held>, $place> {
+                pub fn step(&mut self) -> Held<$held> {
+                    self.try_step().unwrap_or_else(|(e, _)| replace_failed(e))
+                }
+
+                pub fn try_step(&mut self) -> StepResult<Held<$held>, TryError> {
+                    let (held, place) = self.0.unwrap();
+                    match place.swap_with_lock(held.0) {
+                        Ok(()) => Ok(held),
+                        Err(e) => Err((e, held)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_held_lock! {
+    &'a Mutex<T>;
+    &'a RwLock<T>;
+    IgnorePoison<&'a Mutex<T>>;
+    IgnorePoison<&'a RwLock<T>>;
+}
+
+impl_held_fallback! {
+    &'a Mutex<T>, Takeable, Clone;
+    &'a RwLock<T>, Takeable, Clone;
+    IgnorePoison<&'a Mutex<T>>, Takeable, Clone;
+    IgnorePoison<&'a RwLock<T>>, Takeable, Clone;
+}
+
+macro_rules! impl_lock_place {
+    ($($place:ty;)*) => {
+        $(
+            impl<'a, T> Replace<T> for $place {
+                fn replace(self, value: T) -> T {
+                    let mut r = self.write_lock().unwrap_or_else(|e| replace_failed(e));
+                    mem::replace(&mut *r, value)
+                }
+            }
+
+            impl<'a, T> TryReplace<T> for $place {
+                type Error = TryError;
+                fn try_replace(self, value: T) -> Result<T, (Self::Error, T)> {
+                    match self.write_lock() {
+                        Ok(mut r) => Ok(mem::replace(&mut *r, value)),
+                        Err(e) => Err((e, value)),
+                    }
+                }
+            }
+
+            impl<'a, T: Copy> CopyOut<T> for $place {
+                fn copy_out(self) -> T {
+                    *self.read_lock().unwrap_or_else(|e| replace_failed(e))
+                }
+            }
+
+            impl<'a, T: Clone> CloneOut<T> for $place {
+                fn clone_out(self) -> T {
+                    self.read_lock().unwrap_or_else(|e| replace_failed(e)).clone()
+                }
+            }
+
+            impl<'a, T> TakeHelper<$place>
+            where
+                T: Copy,
+            {
+                pub fn take(&mut self) -> T {
+                    self.try_take().unwrap_or_else(|e| replace_failed(e))
+                }
+
+                pub fn try_take(&mut self) -> Result<T, TryError> {
+                    let r = self.0 .0 .0 .0 .0.read_lock()?;
+                    Ok(*r)
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Copy
+                }
+
+                pub fn take_no_clone(&mut self) -> T {
+                    self.take()
+                }
+            }
+
+            impl<'a, T> TakeHelper2<$place>
+            where
+                T: Sentinel,
+            {
+                pub fn take(&mut self) -> T {
+                    self.try_take().unwrap_or_else(|e| replace_failed(e))
+                }
+
+                pub fn try_take(&mut self) -> Result<T, TryError> {
+                    let mut r = self.0 .0 .0 .0.write_lock()?;
+                    Ok(mem::replace(&mut *r, T::sentinel()))
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Sentinel
+                }
+
+                pub fn take_no_clone(&mut self) -> T {
+                    self.take()
+                }
+
+                pub fn take_moved(&mut self) -> T {
+                    self.take()
+                }
+            }
+
+            impl<'a, T> TakeHelper3<$place>
+            where
+                T: Default,
+            {
+                pub fn take(&mut self) -> T {
+                    self.try_take().unwrap_or_else(|e| replace_failed(e))
+                }
+
+                pub fn try_take(&mut self) -> Result<T, TryError> {
+                    let mut r = self.0 .0 .0.write_lock()?;
+                    Ok(mem::take(&mut *r))
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Default
+                }
+
+                pub fn take_no_clone(&mut self) -> T {
+                    self.take()
+                }
+
+                pub fn take_moved(&mut self) -> T {
+                    self.take()
+                }
+            }
+
+            impl<'a, T> TakeHelper4<$place>
+            where
+                T: Clone,
+            {
+                pub fn take(&mut self) -> T {
+                    self.try_take().unwrap_or_else(|e| replace_failed(e))
+                }
+
+                pub fn try_take(&mut self) -> Result<T, TryError> {
+                    let r = self.0 .0.read_lock()?;
+                    Ok(r.clone())
+                }
+
+                pub fn strategy(&self) -> Strategy {
+                    Strategy::Clone
+                }
+
+                pub fn take_no_clone(&mut self) -> T
+                where
+                    T: CloneRejected,
+                {
+                    unreachable!()
+                }
+
+                pub fn take_moved(&mut self) -> T
+                where
+                    T: Movable,
+                {
+                    unreachable!()
+                }
+            }
+
+            impl<'a, T> TakeHelper5<$place> {
+                pub fn take(&mut self) -> T
+                where
+                    T: Takeable,
+                {
+                    unreachable!()
+                }
+
+                pub fn try_take(&mut self) -> Result<T, TryError>
+                where
+                    T: Takeable,
+                {
+                    unreachable!()
+                }
+
+                pub fn strategy(&self) -> Strategy
+                where
+                    T: Takeable,
+                {
+                    unreachable!()
+                }
+
+                pub fn take_no_clone(&mut self) -> T
+                where
+                    T: Takeable,
+                {
+                    unreachable!()
+                }
+
+                pub fn take_moved(&mut self) -> T
+                where
+                    T: Movable,
+                {
+                    unreachable!()
+                }
+            }
+        )*
+    };
+}
+
+impl_lock_place! {
+    &'a Mutex<T>;
+    &'a RwLock<T>;
+    IgnorePoison<&'a Mutex<T>>;
+    IgnorePoison<&'a RwLock<T>>;
+}
@@ -2,6 +2,8 @@ use core::cell::{Cell, RefCell};
 use core::mem;
 use core::ops::{Deref, DerefMut};
 
+use crate::{CloneRejected, Sentinel, Strategy, TryError};
+
 /// Takes out the value from a reference.
 ///
 /// ## Usage
@@ -14,76 +16,242 @@ use core::ops::{Deref, DerefMut};
 /// It also supports references to cells:
 /// [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell).
 ///
+/// For atomics, you can specify the memory ordering after a semicolon.
+/// See [`ReplaceOrdered`](crate::ReplaceOrdered) for details.
+///
+/// ```rust
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// let x = AtomicBool::new(true);
+/// assert!(omniswap::take!(&x; Ordering::Acquire));
+/// ```
+///
+/// The strategy can be pinned with a modifier:
+/// `@copy`, `@sentinel`, `@default` or `@clone`.
+/// It fails to compile if the value type does not support the strategy.
+/// See [Requirements](#requirements) for the strategies.
+///
+/// ```rust
+/// # use std::rc::Rc;
+/// let mut x = Rc::new(vec![1, 2, 3]);
+/// // Bumps the reference count instead of building a default `Rc<Vec<i32>>`.
+/// let y = omniswap::take!(@clone &mut x);
+/// assert_eq!(Rc::strong_count(&x), 2);
+/// # drop(y);
+/// ```
+///
+/// You can also give the sentinel value explicitly with `with`, as in [`take_or!`](crate::take_or!).
+/// Like the other `with` forms, it evaluates the sentinel before the place.
+///
+/// ```rust
+/// let mut x = vec![1, 2, 3];
+/// let mut buf = Vec::with_capacity(16);
+/// buf = omniswap::take!(with buf; &mut x);
+/// assert_eq!(buf, [1, 2, 3]);
+/// assert_eq!(x.capacity(), 16);
+/// ```
+///
 /// ## Requirements
 ///
-/// It requires the value type to satisfy [`Default`](std::default::Default) or [`Clone`](std::clone::Clone).
+/// It requires the value type to satisfy [`Sentinel`](crate::Sentinel), [`Default`](std::default::Default) or [`Clone`](std::clone::Clone).
 ///
 /// If multiple traits can apply, the behavior is determined
 /// in the following order:
 ///
 /// 1. If [`T: Copy`](std::marker::Copy) it copies the value from the seference.
-/// 2. Otherwise, if [`T: Default`](std::default::Default), it swaps the value with the default value.
-/// 3. Otherwise, if [`T: Clone`](std::clone::Clone), it clones out the value from the reference.
+/// 2. Otherwise, if [`T: Sentinel`](crate::Sentinel), it swaps the value with the sentinel value.
+/// 3. Otherwise, if [`T: Default`](std::default::Default), it swaps the value with the default value.
+/// 4. Otherwise, if [`T: Clone`](std::clone::Clone), it clones out the value from the reference.
 ///
 /// Additionally, if the reference is [`&Cell<T>`](std::cell::Cell), `T` must satisfy
-/// [`Default`](std::default::Default) or [`Copy`](std::marker::Copy). [`Clone`](std::clone::Clone) alone does not suffice.
+/// [`Sentinel`](crate::Sentinel), [`Default`](std::default::Default) or [`Copy`](std::marker::Copy). [`Clone`](std::clone::Clone) alone does not suffice.
+///
+/// Otherwise, it fails to compile with an error about `Takeable` or `CellTakeable`,
+/// which lists the missing traits.
 #[macro_export]
 macro_rules! take {
+    (@copy $p: expr) => {
+        $crate::take_copy($p)
+    };
+    (@sentinel $p: expr) => {
+        $crate::take_sentinel($p)
+    };
+    (@default $p: expr) => {
+        $crate::take_default($p)
+    };
+    (@clone $p: expr) => {
+        $crate::take_clone($p)
+    };
+    (with $sentinel: expr; $p: expr) => {{
+        let sentinel = $sentinel;
+        $crate::Replace::replace($p, sentinel)
+    }};
     ($p: expr) => {
         $crate::TakeHelper::new($p).take()
     };
     ($p: expr,) => {
         $crate::take!($p)
     };
+    ($p: expr; $order: expr) => {
+        $crate::ReplaceOrdered::take_ordered($p, $order)
+    };
 }
 
-/// Internal type used in [`take!`].
+/// Takes out the value from a reference, leaving the given sentinel value.
+///
+/// Unlike [`take!`], it does not need [`Sentinel`](crate::Sentinel) or [`Default`],
+/// and it always moves the value out, even if it is [`Copy`].
+/// It supports the same places as [`Replace`](crate::Replace),
+/// such as `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell).
+///
+/// The place and the sentinel are evaluated in the order of appearance.
+/// Note that this differs from `take!(with ...)`, where the sentinel comes first.
 ///
-/// Please use [`take!`] instead.
-pub struct TakeHelper<T>(TakeHelper2<T>);
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// let x = RefCell::new(String::from("x"));
+/// assert_eq!(omniswap::take_or!(&x, String::from("-")), "x");
+/// assert_eq!(x.into_inner(), "-");
+/// ```
+#[macro_export]
+macro_rules! take_or {
+    ($p: expr, $sentinel: expr) => {
+        match $p {
+            place => $crate::Replace::replace(place, $sentinel),
+        }
+    };
+    ($p: expr, $sentinel: expr,) => {
+        $crate::take_or!($p, $sentinel)
+    };
+}
 
-/// Internal type used in [`take!`].
+/// A fallible variant of [`take!`].
+///
+/// It returns [`Err`] instead of panicking if the place is unavailable,
+/// for example because the [`RefCell`](std::cell::RefCell) is already borrowed.
+/// The place is left untouched in that case.
+///
+/// ## Usage
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// let x = RefCell::new(vec![42]);
+/// let guard = x.borrow();
+/// assert!(omniswap::try_take!(&x).is_err());
+/// drop(guard);
+/// assert_eq!(omniswap::try_take!(&x).unwrap(), vec![42]);
+/// assert_eq!(x.into_inner(), vec![]);
+/// ```
 ///
-/// Please use [`take!`] instead.
-pub struct TakeHelper2<T>(TakeHelper3<T>);
+/// The requirements are the same as [`take!`].
+#[macro_export]
+macro_rules! try_take {
+    ($p: expr) => {
+        $crate::TakeHelper::new($p).try_take()
+    };
+    ($p: expr,) => {
+        $crate::try_take!($p)
+    };
+}
 
-/// Internal type used in [`take!`].
+/// Internal type used in [`take!`] and [`try_take!`].
 ///
-/// Please use [`take!`] instead.
-pub struct TakeHelper3<T>(T);
+/// Please use [`take!`] or [`try_take!`] instead.
+pub struct TakeHelper<T>(pub(crate) TakeHelper2<T>);
+
+/// Internal type used in [`take!`] and [`try_take!`].
+///
+/// Please use [`take!`] or [`try_take!`] instead.
+pub struct TakeHelper2<T>(pub(crate) TakeHelper3<T>);
+
+/// Internal type used in [`take!`] and [`try_take!`].
+///
+/// Please use [`take!`] or [`try_take!`] instead.
+pub struct TakeHelper3<T>(pub(crate) TakeHelper4<T>);
+
+/// Internal type used in [`take!`] and [`try_take!`].
+///
+/// Please use [`take!`] or [`try_take!`] instead.
+pub struct TakeHelper4<T>(pub(crate) TakeHelper5<T>);
+
+/// Internal type used in [`take!`] and [`try_take!`].
+///
+/// Please use [`take!`] or [`try_take!`] instead.
+///
+/// It catches the places for which no other level applies,
+/// so that the error is reported through [`Takeable`] or [`CellTakeable`].
+pub struct TakeHelper5<T>(pub(crate) T);
 
 impl<T> TakeHelper<T> {
     pub fn new(inner: T) -> Self {
-        TakeHelper(TakeHelper2(TakeHelper3(inner)))
+        TakeHelper(TakeHelper2(TakeHelper3(TakeHelper4(TakeHelper5(inner)))))
     }
 }
 
-impl<'a, T> TakeHelper<&'a mut T>
+impl<T> TakeHelper<&mut T>
 where
     T: Copy,
 {
     pub fn take(&mut self) -> T {
-        *self.0 .0 .0
+        *self.0 .0 .0 .0 .0
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Copy
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
     }
 }
 
-impl<'a, T> TakeHelper<&'a Cell<T>>
+impl<T> TakeHelper<&Cell<T>>
 where
     T: Copy,
 {
     pub fn take(&mut self) -> T {
-        self.0 .0 .0.get()
+        self.0 .0 .0 .0 .0.get()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Copy
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
     }
 }
 
-impl<'a, T> TakeHelper<&'a RefCell<T>>
+impl<T> TakeHelper<&RefCell<T>>
 where
     T: Copy,
 {
     pub fn take(&mut self) -> T {
-        let r = self.0 .0 .0.borrow();
+        let r = self.0 .0 .0 .0 .0.borrow();
         *r
     }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        let r = self.0 .0 .0 .0 .0.try_borrow()?;
+        Ok(*r)
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Copy
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
 }
 
 impl<T> Deref for TakeHelper<T> {
@@ -99,62 +267,412 @@ impl<T> DerefMut for TakeHelper<T> {
     }
 }
 
-impl<'a, T> TakeHelper2<&'a mut T>
+impl<T> TakeHelper2<&mut T>
+where
+    T: Sentinel,
+{
+    pub fn take(&mut self) -> T {
+        mem::replace(self.0 .0 .0 .0, T::sentinel())
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Sentinel
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
+    }
+}
+
+impl<T> TakeHelper2<&Cell<T>>
+where
+    T: Sentinel,
+{
+    pub fn take(&mut self) -> T {
+        self.0 .0 .0 .0.replace(T::sentinel())
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Sentinel
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
+    }
+}
+
+impl<T> TakeHelper2<&RefCell<T>>
+where
+    T: Sentinel,
+{
+    pub fn take(&mut self) -> T {
+        let mut r = self.0 .0 .0 .0.borrow_mut();
+        mem::replace(&mut *r, T::sentinel())
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        let mut r = self.0 .0 .0 .0.try_borrow_mut()?;
+        Ok(mem::replace(&mut *r, T::sentinel()))
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Sentinel
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
+    }
+}
+
+impl<T> Deref for TakeHelper2<T> {
+    type Target = TakeHelper3<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for TakeHelper2<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> TakeHelper3<&mut T>
 where
     T: Default,
 {
     pub fn take(&mut self) -> T {
-        mem::take(self.0 .0)
+        mem::take(self.0 .0 .0)
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Default
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
     }
 }
 
-impl<'a, T> TakeHelper2<&'a Cell<T>>
+impl<T> TakeHelper3<&Cell<T>>
 where
     T: Default,
 {
     pub fn take(&mut self) -> T {
-        self.0 .0.take()
+        self.0 .0 .0.take()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Default
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
     }
 }
 
-impl<'a, T> TakeHelper2<&'a RefCell<T>>
+impl<T> TakeHelper3<&RefCell<T>>
 where
     T: Default,
 {
     pub fn take(&mut self) -> T {
-        let mut r = self.0 .0.borrow_mut();
+        let mut r = self.0 .0 .0.borrow_mut();
         mem::take(&mut *r)
     }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        let mut r = self.0 .0 .0.try_borrow_mut()?;
+        Ok(mem::take(&mut *r))
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Default
+    }
+
+    pub fn take_no_clone(&mut self) -> T {
+        self.take()
+    }
+
+    pub fn take_moved(&mut self) -> T {
+        self.take()
+    }
 }
 
-impl<T> Deref for TakeHelper2<T> {
-    type Target = TakeHelper3<T>;
+impl<T> Deref for TakeHelper3<T> {
+    type Target = TakeHelper4<T>;
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<T> DerefMut for TakeHelper2<T> {
+impl<T> DerefMut for TakeHelper3<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
 
-impl<'a, T> TakeHelper3<&'a mut T>
+impl<T> TakeHelper4<&mut T>
 where
     T: Clone,
 {
     pub fn take(&mut self) -> T {
-        self.0.clone()
+        self.0 .0.clone()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        Ok(self.take())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Clone
+    }
+
+    pub fn take_no_clone(&mut self) -> T
+    where
+        T: CloneRejected,
+    {
+        unreachable!()
+    }
+
+    pub fn take_moved(&mut self) -> T
+    where
+        T: Movable,
+    {
+        unreachable!()
     }
 }
 
-impl<'a, T> TakeHelper3<&'a RefCell<T>>
+impl<T> TakeHelper4<&RefCell<T>>
 where
     T: Clone,
 {
     pub fn take(&mut self) -> T {
-        let r = self.0.borrow();
+        let r = self.0 .0.borrow();
         r.clone()
     }
+
+    pub fn try_take(&mut self) -> Result<T, TryError> {
+        let r = self.0 .0.try_borrow()?;
+        Ok(r.clone())
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        Strategy::Clone
+    }
+
+    pub fn take_no_clone(&mut self) -> T
+    where
+        T: CloneRejected,
+    {
+        unreachable!()
+    }
+
+    pub fn take_moved(&mut self) -> T
+    where
+        T: Movable,
+    {
+        unreachable!()
+    }
+}
+
+impl<T> Deref for TakeHelper4<T> {
+    type Target = TakeHelper5<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for TakeHelper4<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Internal trait used in [`take!`] and [`swap!`](crate::swap!).
+///
+/// It is not implemented for any type, and cannot be implemented outside this crate,
+/// so that a value type without a way to take it out is reported as an error.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "cannot take out `{Self}` from the place",
+    label = "`{Self}` is neither `Copy`, `Sentinel`, `Default` nor `Clone`",
+    note = "implement `Sentinel`, `Default` or `Clone` for `{Self}`",
+    note = "alternatively, use `swap_index!`, `swap_unchecked!`, `std::mem::swap`, or `swap!` with a `Cell` or `RefCell` as the first place"
+)]
+pub trait Takeable: sealed::Sealed {}
+
+/// Internal trait used in [`take!`] and [`swap!`](crate::swap!).
+///
+/// A variant of [`Takeable`] for [`&Cell<T>`](Cell), where [`Clone`] does not suffice.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "cannot take out `{Self}` from the `Cell`",
+    label = "`{Self}` is neither `Copy`, `Sentinel` nor `Default`",
+    note = "`Clone` is not enough for `Cell`, because the value cannot be borrowed in place",
+    note = "implement `Sentinel` or `Default` for `{Self}`, or use `swap!` with the `Cell` as the first place to swap it directly"
+)]
+pub trait CellTakeable: sealed::Sealed {}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Internal trait used in [`transfer!`](crate::transfer!).
+///
+/// It is not implemented for any type, and cannot be implemented outside this crate,
+/// so that a value that would be copied or cloned out instead of moved is reported as an error.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "cannot move `{Self}` out of the place, leaving a sentinel behind",
+    label = "`{Self}` is neither `Sentinel` nor `Default`",
+    note = "`transfer!` never copies or clones the value, so that the source is always left with the sentinel",
+    note = "implement `Sentinel` or `Default` for `{Self}`, or use `swap!` to exchange the values instead"
+)]
+pub trait Movable: sealed::Sealed {}
+
+impl<T> TakeHelper5<&mut T> {
+    pub fn take(&mut self) -> T
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError>
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn strategy(&self) -> Strategy
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_no_clone(&mut self) -> T
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_moved(&mut self) -> T
+    where
+        T: Movable,
+    {
+        unreachable!()
+    }
+}
+
+impl<T> TakeHelper5<&Cell<T>> {
+    pub fn take(&mut self) -> T
+    where
+        T: CellTakeable,
+    {
+        unreachable!()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError>
+    where
+        T: CellTakeable,
+    {
+        unreachable!()
+    }
+
+    pub fn strategy(&self) -> Strategy
+    where
+        T: CellTakeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_no_clone(&mut self) -> T
+    where
+        T: CellTakeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_moved(&mut self) -> T
+    where
+        T: Movable,
+    {
+        unreachable!()
+    }
+}
+
+impl<T> TakeHelper5<&RefCell<T>> {
+    pub fn take(&mut self) -> T
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn try_take(&mut self) -> Result<T, TryError>
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn strategy(&self) -> Strategy
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_no_clone(&mut self) -> T
+    where
+        T: Takeable,
+    {
+        unreachable!()
+    }
+
+    pub fn take_moved(&mut self) -> T
+    where
+        T: Movable,
+    {
+        unreachable!()
+    }
 }
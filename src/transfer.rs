@@ -0,0 +1,98 @@
+/// Moves the value from one place to another, and returns the old value of the destination.
+///
+/// It moves the value out of the first place, leaving the sentinel behind,
+/// and puts it to the second place as in [`Replace`](crate::Replace).
+/// The source and the destination may be any mix of
+/// `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell),
+/// and may even be the same place, as described below.
+///
+/// ## Usage
+///
+/// ```rust
+/// struct Inventory {
+///     grid: Vec<Vec<String>>,
+///     slot: String,
+/// }
+///
+/// let mut inv = Inventory {
+///     grid: vec![vec![String::from("sword")]],
+///     slot: String::from("shield"),
+/// };
+/// let old = omniswap::transfer!(&mut inv.grid[0][0], &mut inv.slot);
+/// assert_eq!(old, "shield");
+/// assert_eq!(inv.grid[0][0], "");
+/// assert_eq!(inv.slot, "sword");
+/// ```
+///
+/// If the two places are the same, the value is put back and the sentinel is returned.
+///
+/// ## Requirements
+///
+/// The value is always moved, so the value type must satisfy
+/// [`Sentinel`](crate::Sentinel) or [`Default`], which is tried first in this order.
+/// Even a [`Copy`] value is moved out, leaving the sentinel behind.
+///
+/// ```rust
+/// let mut from = 42;
+/// let mut to = 0;
+/// omniswap::transfer!(&mut from, &mut to);
+/// assert_eq!((from, to), (0, 42));
+/// ```
+///
+/// Unlike [`take!`](crate::take!), it never copies or clones the value out,
+/// so a value type that is [`Copy`] or [`Clone`] but neither [`Sentinel`](crate::Sentinel)
+/// nor [`Default`] fails to compile.
+///
+/// ## Evaluation order
+///
+/// It evaluates the first argument and then the second one, each exactly once.
+/// If the place syntax is used, the index expressions are evaluated first,
+/// in the order of appearance.
+///
+/// ## Panics
+///
+/// If the second place is unavailable, for example because the
+/// [`RefCell`](std::cell::RefCell) is already borrowed,
+/// it puts the value back to the first place and then panics.
+/// To put the value back, it evaluates the first argument again.
+/// If evaluating the second argument panics, the value is dropped instead,
+/// and the first place is left with the sentinel.
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use std::panic::{catch_unwind, AssertUnwindSafe};
+/// let from = RefCell::new(String::from("payload"));
+/// let to = RefCell::new(String::from("old"));
+/// let guard = to.borrow();
+/// let result = catch_unwind(AssertUnwindSafe(|| omniswap::transfer!(&from, &to)));
+/// assert!(result.is_err());
+/// drop(guard);
+/// assert_eq!((from.into_inner(), to.into_inner()), ("payload".into(), "old".into()));
+/// ```
+#[macro_export]
+macro_rules! transfer {
+    ($($args: tt)*) => {
+        $crate::__place_args!(__transfer_impl panic transfer [] $($args)*)
+    };
+}
+
+/// Internal macro used in [`transfer!`].
+///
+/// Please use [`transfer!`] instead.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __transfer_impl {
+    (@start panic transfer [$from: expr, $to: expr]) => {{
+        let value = $crate::TakeHelper::new($from).take_moved();
+        match $crate::StepHelper::new(value, $to).try_step() {
+            ::core::result::Result::Ok(value) => value,
+            ::core::result::Result::Err((error, value)) => {
+                let _ = $crate::StepHelper::new(value, $from).try_step();
+                $crate::replace_failed(error)
+            }
+        }
+    }};
+    (@start $mode: tt transfer [$($out: expr),*]) => {
+        ::core::compile_error!("expected exactly two places")
+    };
+}
@@ -0,0 +1,146 @@
+use core::mem;
+use core::ptr;
+
+/// Swaps values of two references without a sentinel.
+///
+/// This is a variant of [`swap!`](crate::swap!) that works for any type,
+/// including those that are neither [`Default`](std::default::Default)
+/// nor [`Clone`](std::clone::Clone), such as `Box<dyn FnMut()>` or `File`.
+///
+/// ## Usage
+///
+/// ```rust
+/// let mut a: [[Box<dyn Fn() -> i32>; 2]; 2] = [
+///     [Box::new(|| 1), Box::new(|| 2)],
+///     [Box::new(|| 3), Box::new(|| 4)],
+/// ];
+/// // SAFETY: evaluating `&mut a[_][_]` does not touch the elements.
+/// unsafe {
+///     omniswap::swap_unchecked!(&mut a[0][0], &mut a[1][1]);
+/// }
+/// assert_eq!((a[0][0](), a[1][1]()), (4, 1));
+/// ```
+///
+/// Only `&mut T` is supported; cells always have a safe alternative
+/// such as [`Cell::swap`](std::cell::Cell::swap).
+///
+/// ## How it works
+///
+/// Instead of a sentinel, the value of the first place is moved out bitwise,
+/// leaving a stale copy behind. The stale copy is overwritten
+/// without being dropped when the value is put back.
+///
+/// Each reference is used only until the next argument is evaluated,
+/// so no two of them are alive at the same time.
+/// This is why the places are not simply converted to raw pointers and passed to
+/// [`ptr::swap`](std::ptr::swap): when a place is reached through
+/// [`IndexMut`](std::ops::IndexMut) (as in `&mut v[i]` for a `Vec`),
+/// evaluating the second place invalidates the pointer to the first one
+/// under Stacked Borrows.
+///
+/// If evaluating another argument panics while the value is moved out,
+/// the process is aborted instead of dropping the value twice.
+/// A panic in the first argument unwinds as usual, since nothing has been moved out yet.
+///
+/// ## Safety
+///
+/// Evaluating the arguments must not read, write, move or drop
+/// the values in the places denoted by the arguments.
+/// Merely computing the reference, as in `&mut a[i][j]` or `&mut v[i]`, is fine.
+///
+/// For example, the following is undefined behavior because the second argument
+/// drops the stale copy left in `v[0]`:
+///
+/// ```rust,no_run
+/// let mut v = vec![Box::new(1), Box::new(2)];
+/// unsafe {
+///     omniswap::swap_unchecked!(&mut v[0], {
+///         v[0] = Box::new(3);
+///         &mut v[1]
+///     });
+/// }
+/// ```
+///
+/// ## Evaluation order
+///
+/// Same as [`swap!`](crate::swap!): it evaluates the arguments in the order of appearance,
+/// and then **the first argument again** to put the value back.
+#[macro_export]
+macro_rules! swap_unchecked {
+    ($x: expr, $y: expr) => {
+        $crate::rotate_unchecked!($x, $y)
+    };
+    ($x: expr, $y: expr,) => {
+        $crate::rotate_unchecked!($x, $y)
+    };
+}
+
+/// A variant of [`swap_unchecked!`] that works for more than two values.
+///
+/// See [`swap_unchecked!`] for the safety requirements.
+///
+/// ## Example
+///
+/// ```rust
+/// let mut v = vec![Box::new('a'), Box::new('b'), Box::new('c')];
+/// // SAFETY: evaluating `&mut v[_]` does not touch the elements.
+/// unsafe {
+///     omniswap::rotate_unchecked!(&mut v[0], &mut v[1], &mut v[2]);
+/// }
+/// assert_eq!(v, [Box::new('c'), Box::new('a'), Box::new('b')]);
+/// ```
+#[macro_export]
+macro_rules! rotate_unchecked {
+    ($x: expr, $($y: expr),*) => {
+        {
+            let (value, helper) = $crate::UncheckedHelper::read($x);
+            $(
+                let value = ::core::mem::replace($y, value);
+            )*
+            helper.write($x, value);
+        }
+    };
+    ($x: expr) => {
+        $crate::rotate_unchecked!($x,)
+    };
+    ($x: expr, $($y: expr),*,) => {
+        $crate::rotate_unchecked!($x, $($y),*)
+    };
+}
+
+/// Internal type used in [`rotate_unchecked!`].
+///
+/// Please use [`rotate_unchecked!`] instead.
+///
+/// It aborts the process if dropped during unwinding,
+/// that is, if something panics while a value is moved out.
+pub struct UncheckedHelper(());
+
+impl UncheckedHelper {
+    /// Moves out the value, leaving a stale copy behind,
+    /// and returns it along with the helper guarding the stale copy.
+    ///
+    /// # Safety
+    ///
+    /// The stale copy must not be used until it is overwritten with [`UncheckedHelper::write`].
+    pub unsafe fn read<T>(place: &mut T) -> (T, Self) {
+        (ptr::read(place), UncheckedHelper(()))
+    }
+
+    /// Overwrites a stale copy without dropping it.
+    ///
+    /// # Safety
+    ///
+    /// `place` must hold the stale copy left by [`UncheckedHelper::read`].
+    pub unsafe fn write<T>(self, place: &mut T, value: T) {
+        ptr::write(place, value);
+        mem::forget(self);
+    }
+}
+
+impl Drop for UncheckedHelper {
+    fn drop(&mut self) {
+        // Panicking during unwinding aborts the process.
+        panic!("omniswap: panicked while a value was moved out");
+    }
+}
@@ -0,0 +1,168 @@
+use crate::TryReplace;
+
+/// Reborrows a place so that it can be accessed more than once.
+///
+/// It is implemented for `&mut T` and for shared references such as
+/// [`&Cell<T>`](core::cell::Cell) and [`&RefCell<T>`](core::cell::RefCell),
+/// and used in [`update!`](crate::update!) to evaluate the place only once.
+pub trait Reborrow {
+    type Target<'b>
+    where
+        Self: 'b;
+    fn reborrow(&mut self) -> Self::Target<'_>;
+}
+
+impl<T: ?Sized> Reborrow for &mut T {
+    type Target<'b>
+        = &'b mut T
+    where
+        Self: 'b;
+    fn reborrow(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<'a, T: ?Sized> Reborrow for &'a T {
+    type Target<'b>
+        = &'a T
+    where
+        Self: 'b;
+    fn reborrow(&mut self) -> &'a T {
+        self
+    }
+}
+
+/// Internal type used in [`update!`](crate::update!).
+///
+/// Please use [`update!`](crate::update!) instead.
+///
+/// It writes the new value back to the place,
+/// or, if dropped before that because the closure panicked, applies the panic policy
+/// chosen with `@restore` or `@abort`.
+pub struct UpdateGuard<P, T>
+where
+    P: TryReplace<T>,
+{
+    place: Option<P>,
+    backup: Option<T>,
+    abort: bool,
+}
+
+impl<P, T> UpdateGuard<P, T>
+where
+    P: TryReplace<T>,
+{
+    pub fn restore(place: P, backup: T) -> Self {
+        UpdateGuard {
+            place: Some(place),
+            backup: Some(backup),
+            abort: false,
+        }
+    }
+
+    pub fn abort(place: P) -> Self {
+        UpdateGuard {
+            place: Some(place),
+            backup: None,
+            abort: true,
+        }
+    }
+
+    pub fn finish(mut self, value: T) {
+        let place = self.place.take().expect("UpdateGuard used twice");
+        place.replace(value);
+    }
+}
+
+impl<P, T> Drop for UpdateGuard<P, T>
+where
+    P: TryReplace<T>,
+{
+    fn drop(&mut self) {
+        let Some(place) = self.place.take() else {
+            return;
+        };
+        if self.abort {
+            // Panicking while unwinding aborts the process.
+            panic!("the closure passed to update! panicked");
+        }
+        if let Some(backup) = self.backup.take() {
+            // Do not panic again if the place is unavailable; leave it as is.
+            let _ = place.try_replace(backup);
+        }
+    }
+}
+
+/// Transforms the value of a place through a closure.
+///
+/// It takes out the value as in [`take!`](crate::take!), passes it to the closure,
+/// and puts the result back, as in `*place = f(take(place))`.
+/// It supports the same places as [`take!`](crate::take!),
+/// such as `&mut T`, [`&Cell<T>`](std::cell::Cell) and [`&RefCell<T>`](std::cell::RefCell).
+///
+/// ## Usage
+///
+/// ```rust
+/// #[derive(Debug, PartialEq, Default)]
+/// enum State {
+///     #[default]
+///     Idle,
+///     Running(Vec<u32>),
+///     Done(usize),
+/// }
+///
+/// let mut state = State::Running(vec![1, 2, 3]);
+/// omniswap::update!(&mut state, |state| match state {
+///     State::Running(jobs) => State::Done(jobs.len()),
+///     state => state,
+/// });
+/// assert_eq!(state, State::Done(3));
+/// ```
+///
+/// ## Panics in the closure
+///
+/// By default, if the closure panics, the place is left as [`take!`](crate::take!) left it,
+/// for example with the sentinel or the default value.
+/// Another policy can be chosen with a modifier:
+///
+/// - `@restore` -- puts back a clone of the original value. It requires [`Clone`].
+/// - `@abort` -- aborts the process.
+///
+/// ```rust
+/// # use std::cell::RefCell;
+/// # use std::panic::{catch_unwind, AssertUnwindSafe};
+/// let x = RefCell::new(vec![1, 2, 3]);
+/// let result = catch_unwind(AssertUnwindSafe(|| {
+///     omniswap::update!(@restore &x, |v: Vec<i32>| -> Vec<i32> { panic!("{:?}", v) });
+/// }));
+/// assert!(result.is_err());
+/// assert_eq!(x.into_inner(), [1, 2, 3]);
+/// ```
+///
+/// ## Evaluation order
+///
+/// It evaluates the place and then the closure, each exactly once.
+#[macro_export]
+macro_rules! update {
+    (@restore $p: expr, $f: expr $(,)?) => {{
+        let mut place = $p;
+        let f = $f;
+        let value = $crate::take!($crate::Reborrow::reborrow(&mut place));
+        let backup = ::core::clone::Clone::clone(&value);
+        let guard = $crate::UpdateGuard::restore($crate::Reborrow::reborrow(&mut place), backup);
+        guard.finish(f(value))
+    }};
+    (@abort $p: expr, $f: expr $(,)?) => {{
+        let mut place = $p;
+        let f = $f;
+        let value = $crate::take!($crate::Reborrow::reborrow(&mut place));
+        let guard = $crate::UpdateGuard::abort($crate::Reborrow::reborrow(&mut place));
+        guard.finish(f(value))
+    }};
+    ($p: expr, $f: expr $(,)?) => {{
+        let mut place = $p;
+        let f = $f;
+        let value = $crate::take!($crate::Reborrow::reborrow(&mut place));
+        let _ = $crate::Replace::replace($crate::Reborrow::reborrow(&mut place), f(value));
+    }};
+}
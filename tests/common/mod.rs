@@ -0,0 +1,20 @@
+//! Fixtures shared by the integration tests.
+
+// Each test crate uses only some of the fixtures.
+#![allow(dead_code)]
+
+use omniswap::Sentinel;
+
+/// Neither `Copy`, `Default` nor `Clone`, but has a sentinel.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Token(pub u32);
+
+impl Sentinel for Token {
+    fn sentinel() -> Self {
+        Token(0)
+    }
+}
+
+/// Neither `Copy`, `Default`, `Clone` nor `Sentinel`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Handle(pub u32);
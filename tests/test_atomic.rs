@@ -0,0 +1,91 @@
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicPtr, AtomicU16, AtomicUsize, Ordering};
+
+use omniswap::{rotate, swap, take, Replace, ReplaceOrdered};
+
+#[test]
+fn test_take_atomic() {
+    let x = AtomicUsize::new(42);
+    assert_eq!(take!(&x), 42);
+    assert_eq!(take!(&x; Ordering::Relaxed), 42);
+    // Orderings for stores are weakened for the load.
+    assert_eq!(take!(&x; Ordering::Release), 42);
+    assert_eq!(take!(&x; Ordering::AcqRel), 42);
+    assert_eq!(x.into_inner(), 42);
+}
+
+#[test]
+fn test_replace_atomic() {
+    let x = AtomicI64::new(-1);
+    assert_eq!(Replace::replace(&x, 2), -1);
+    assert_eq!(ReplaceOrdered::replace_ordered(&x, 3, Ordering::Release), 2);
+    assert_eq!(x.into_inner(), 3);
+}
+
+#[test]
+fn test_swap_atomic() {
+    let x = AtomicBool::new(true);
+    let y = AtomicBool::new(false);
+    swap!(&x, &y);
+    assert_eq!(
+        (x.load(Ordering::SeqCst), y.load(Ordering::SeqCst)),
+        (false, true)
+    );
+    swap!(&x, &y; Ordering::AcqRel);
+    assert_eq!((x.into_inner(), y.into_inner()), (true, false));
+}
+
+#[test]
+fn test_swap_atomic_mixed() {
+    let x = AtomicU16::new(1);
+    let mut y = 2;
+    let z = Cell::new(3);
+    rotate!(&x, &mut y, &z);
+    assert_eq!((x.into_inner(), y, z.get()), (3, 1, 2));
+}
+
+#[test]
+fn test_swap_atomic_ptr() {
+    let mut a = 1;
+    let mut b = 2;
+    let x = AtomicPtr::new(&mut a as *mut i32);
+    let y = AtomicPtr::new(&mut b as *mut i32);
+    swap!(&x, &y; Ordering::SeqCst);
+    assert!(ptr::eq(x.into_inner(), &b));
+    assert!(ptr::eq(y.into_inner(), &a));
+}
+
+#[test]
+fn test_rotate_atomic_ordered() {
+    let a = [
+        AtomicUsize::new(1),
+        AtomicUsize::new(2),
+        AtomicUsize::new(3),
+        AtomicUsize::new(4),
+    ];
+    rotate!(&a[0], &a[1], &a[2], &a[3]; Ordering::Relaxed);
+    assert_eq!(a.map(AtomicUsize::into_inner), [4, 1, 2, 3]);
+}
+
+#[test]
+fn test_swap_atomic_ordered_eval_order() {
+    let a = [AtomicUsize::new(1), AtomicUsize::new(2)];
+    let mut log = vec![];
+    swap!(
+        {
+            log.push("x");
+            &a[0]
+        },
+        {
+            log.push("y");
+            &a[1]
+        };
+        {
+            log.push("order");
+            Ordering::SeqCst
+        }
+    );
+    assert_eq!(log, ["order", "x", "y", "x"]);
+    assert_eq!(a.map(AtomicUsize::into_inner), [2, 1]);
+}
@@ -0,0 +1,101 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use omniswap::{cswap, swap_if};
+
+#[test]
+fn test_swap_if() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    swap_if!(false, &mut x, &mut y);
+    assert_eq!((x.as_str(), y.as_str()), ("x", "y"));
+    swap_if!(true, &mut x, &mut y);
+    assert_eq!((x.as_str(), y.as_str()), ("y", "x"));
+
+    let mut a = vec![vec![3], vec![1]];
+    swap_if!(a[0] > a[1], mut a[0], mut a[1],);
+    assert_eq!(a, [[1], [3]]);
+}
+
+#[test]
+fn test_swap_if_with_sentinel() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    let s = swap_if!(false, with String::from("-"); &mut x, &mut y);
+    assert_eq!((x.as_str(), y.as_str(), s.as_str()), ("x", "y", "-"));
+    let s = swap_if!(true, with String::from("-"); &mut x, &mut y);
+    assert_eq!((x.as_str(), y.as_str(), s.as_str()), ("y", "x", "-"));
+
+    let a = AtomicU8::new(1);
+    let b = AtomicU8::new(2);
+    swap_if!(false, &a, &b; Ordering::SeqCst);
+    swap_if!(true, &a, &b; Ordering::SeqCst);
+    assert_eq!((a.into_inner(), b.into_inner()), (2, 1));
+}
+
+#[test]
+fn test_swap_if_eval_order() {
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    swap_if!(
+        {
+            log.push(0);
+            false
+        },
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        }
+    );
+    assert_eq!((x, y), (1, 2));
+    assert_eq!(log, vec![0]);
+}
+
+#[test]
+fn test_cswap_array() {
+    // A sorting network for four elements.
+    fn sort4(a: &mut [i64; 4]) {
+        for (i, j) in [(0, 1), (2, 3), (0, 2), (1, 3), (1, 2)] {
+            cswap!(a[i] > a[j], mut a[i], mut a[j]);
+        }
+    }
+
+    let mut a = [3, -1, 4, 1];
+    sort4(&mut a);
+    assert_eq!(a, [-1, 1, 3, 4]);
+
+    let mut a = [i64::MAX, 0, i64::MIN, -1];
+    sort4(&mut a);
+    assert_eq!(a, [i64::MIN, -1, 0, i64::MAX]);
+
+    let mut a = [u8::MAX, 0];
+    cswap!(true, mut a[0], mut a[1]);
+    assert_eq!(a, [0, u8::MAX]);
+    cswap!(false, mut a[0], mut a[1]);
+    assert_eq!(a, [0, u8::MAX]);
+
+    // Overlapping places are left as is.
+    let mut a = [7u128, 9];
+    cswap!(true, mut a[1], mut a[1]);
+    assert_eq!(a, [7, 9]);
+}
+
+#[test]
+fn test_cswap_cell() {
+    let x = Cell::new(1usize);
+    let y = RefCell::new(2usize);
+    cswap!(true, &x, &y);
+    assert_eq!((x.get(), *y.borrow()), (2, 1));
+    cswap!(false, &x, &y);
+    assert_eq!((x.get(), *y.borrow()), (2, 1));
+
+    let mut z = -3i16;
+    let w = Cell::new(5i16);
+    cswap!(true, &mut z, &w);
+    assert_eq!((z, w.get()), (5, -3));
+}
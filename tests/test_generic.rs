@@ -0,0 +1,160 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+use omniswap::{
+    swap_with, swap_with_clone, swap_with_copy, swap_with_sentinel, take_clone, take_copy,
+    take_default, take_sentinel, CloneOut, CopyOut, Replace, Sentinel,
+};
+
+mod common;
+
+use common::Token;
+
+fn generic_take_default<T: Default, P: Replace<T>>(place: P) -> T {
+    take_default(place)
+}
+
+fn generic_take_sentinel<T: Sentinel, P: Replace<T>>(place: P) -> T {
+    take_sentinel(place)
+}
+
+fn generic_take_copy<T: Copy, P: CopyOut<T>>(place: P) -> T {
+    take_copy(place)
+}
+
+fn generic_take_clone<T: Clone, P: CloneOut<T>>(place: P) -> T {
+    take_clone(place)
+}
+
+#[test]
+fn test_take_default() {
+    let mut x = vec![1];
+    assert_eq!(generic_take_default(&mut x), vec![1]);
+    assert_eq!(x, Vec::<i32>::new());
+
+    let x = Cell::new(String::from("a"));
+    assert_eq!(generic_take_default(&x), "a");
+    assert_eq!(x.into_inner(), "");
+
+    let x = RefCell::new(42);
+    assert_eq!(generic_take_default(&x), 42);
+    assert_eq!(x.into_inner(), 0);
+}
+
+#[test]
+fn test_take_sentinel() {
+    let mut x = Token(42);
+    assert_eq!(generic_take_sentinel(&mut x), Token(42));
+    assert_eq!(x, Token(0));
+
+    let x = RefCell::new(Token(42));
+    assert_eq!(generic_take_sentinel(&x), Token(42));
+    assert_eq!(x.into_inner(), Token(0));
+}
+
+#[test]
+fn test_take_copy() {
+    let mut x = 42;
+    assert_eq!(generic_take_copy(&mut x), 42);
+    assert_eq!(x, 42);
+
+    let x = Cell::new('a');
+    assert_eq!(generic_take_copy(&x), 'a');
+    assert_eq!(x.into_inner(), 'a');
+
+    let x = RefCell::new(1.5);
+    assert_eq!(generic_take_copy(&x), 1.5);
+}
+
+#[test]
+fn test_take_clone() {
+    let mut x = String::from("a");
+    assert_eq!(generic_take_clone(&mut x), "a");
+    assert_eq!(x, "a");
+
+    let x = RefCell::new(vec![1, 2]);
+    assert_eq!(generic_take_clone(&x), [1, 2]);
+    assert_eq!(x.into_inner(), [1, 2]);
+}
+
+#[test]
+fn test_swap_with() {
+    struct Root<T> {
+        a: [T; 3],
+        b: T,
+    }
+
+    fn swap_generic<T: Default>(root: &mut Root<T>, i: usize, j: usize) {
+        swap_with(root, |r| &mut r.a[i], |r| &mut r.a[j]);
+    }
+
+    let mut root = Root {
+        a: [String::from("x"), String::from("y"), String::from("z")],
+        b: String::from("w"),
+    };
+    swap_generic(&mut root, 0, 2);
+    assert_eq!(root.a, ["z", "y", "x"]);
+    swap_generic(&mut root, 1, 1);
+    assert_eq!(root.a, ["z", "y", "x"]);
+    swap_with(&mut root, |r| &mut r.a[1], |r| &mut r.b);
+    assert_eq!((&root.a[1][..], &root.b[..]), ("w", "y"));
+
+    let mut v = vec![1, 2, 3];
+    swap_with(&mut v[..], |s| &mut s[0], |s| s.last_mut().unwrap());
+    assert_eq!(v, [3, 2, 1]);
+}
+
+#[test]
+fn test_swap_with_eval_order() {
+    let mut log = vec![];
+    let mut a = [1, 2];
+    swap_with(
+        &mut a,
+        |a| {
+            log.push(100);
+            &mut a[0]
+        },
+        |a| {
+            // The first projection is not alive here.
+            &mut a[1]
+        },
+    );
+    assert_eq!(a, [2, 1]);
+    assert_eq!(log, [100, 100]);
+}
+
+#[test]
+fn test_swap_with_strategies() {
+    let mut a = [Token(1), Token(2)];
+    swap_with_sentinel(&mut a, |a| &mut a[0], |a| &mut a[1]);
+    assert_eq!(a, [Token(2), Token(1)]);
+
+    let mut a = [1, 2, 3];
+    swap_with_copy(&mut a, |a| &mut a[0], |a| &mut a[2]);
+    assert_eq!(a, [3, 2, 1]);
+
+    let mut a = [Rc::new(1), Rc::new(2)];
+    swap_with_clone(&mut a, |a| &mut a[0], |a| &mut a[1]);
+    assert_eq!((*a[0], *a[1]), (2, 1));
+    swap_with_clone(&mut a, |a| &mut a[1], |a| &mut a[1]);
+    assert_eq!((*a[0], *a[1]), (2, 1));
+}
+
+#[test]
+fn test_swap_with_panic_restore() {
+    // The value taken out of the first place is put back if the second projection panics.
+    let mut a = vec![String::from("x")];
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        swap_with(&mut a, |a| &mut a[0], |a| &mut a[1]);
+    }));
+    assert!(result.is_err());
+    assert_eq!(a, ["x"]);
+
+    let mut a = vec![Token(1)];
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        swap_with_sentinel(&mut a, |a| &mut a[0], |a| &mut a[1]);
+    }));
+    assert!(result.is_err());
+    assert_eq!(a, [Token(1)]);
+}
@@ -0,0 +1,55 @@
+use std::cell::{Cell, RefCell};
+
+use omniswap::{rotate_no_clone, swap_no_clone, take_no_clone};
+
+mod common;
+
+use common::Token;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mesh(Vec<u32>);
+
+#[test]
+fn test_take_no_clone() {
+    let mut x = 42;
+    assert_eq!(take_no_clone!(&mut x), 42);
+    assert_eq!(x, 42);
+
+    let mut x = Token(42);
+    assert_eq!(take_no_clone!(&mut x), Token(42));
+    assert_eq!(x, Token(0));
+
+    let x = RefCell::new(vec![42]);
+    assert_eq!(take_no_clone!(&x), [42]);
+    assert_eq!(x.into_inner(), []);
+}
+
+#[test]
+fn test_swap_no_clone() {
+    let mut a = [Token(1), Token(2)];
+    swap_no_clone!(mut a[0], mut a[1]);
+    assert_eq!(a, [Token(2), Token(1)]);
+
+    // The first place is swapped directly, so `Clone` is not needed.
+    let x = RefCell::new(Mesh(vec![1]));
+    let mut y = Mesh(vec![2]);
+    swap_no_clone!(&x, &mut y);
+    assert_eq!((x.into_inner(), y), (Mesh(vec![2]), Mesh(vec![1])));
+
+    let x = Cell::new(Mesh(vec![1]));
+    let y = Cell::new(Mesh(vec![2]));
+    swap_no_clone!(&x, &y);
+    assert_eq!(
+        (x.into_inner(), y.into_inner()),
+        (Mesh(vec![2]), Mesh(vec![1]))
+    );
+}
+
+#[test]
+fn test_rotate_no_clone() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    let mut z = String::from("z");
+    rotate_no_clone!(&mut x, &mut y, &mut z);
+    assert_eq!((x.as_str(), y.as_str(), z.as_str()), ("z", "x", "y"));
+}
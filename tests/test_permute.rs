@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::permute;
+
+#[test]
+fn test_permute() {
+    let mut a = 'a';
+    let mut b = 'b';
+    let mut c = 'c';
+    let mut d = 'd';
+    let mut e = 'e';
+    permute!((&mut a, &mut b, &mut c), (&mut d, &mut e));
+    assert_eq!((a, b, c, d, e), ('c', 'a', 'b', 'e', 'd'));
+
+    let mut v = vec![String::from("a"), String::from("b"), String::from("c")];
+    permute!((mut v[0], mut v[2]), (mut v[1]),);
+    assert_eq!(v, ["c", "b", "a"]);
+
+    let x = RefCell::new(vec![1]);
+    let y = RefCell::new(vec![2]);
+    let mut z = vec![3];
+    permute!((&x, &y, &mut z));
+    assert_eq!(
+        (x.into_inner(), y.into_inner(), z),
+        (vec![3], vec![1], vec![2])
+    );
+}
+
+#[test]
+fn test_permute_eval_order() {
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    let mut z = 3;
+    let mut w = 4;
+    permute!(
+        (
+            {
+                log.push(100);
+                &mut x
+            },
+            {
+                log.push(200);
+                &mut y
+            },
+            {
+                log.push(300);
+                &mut z
+            }
+        ),
+        ({
+            log.push(400);
+            &mut w
+        },)
+    );
+    assert_eq!((x, y, z, w), (3, 1, 2, 4));
+    assert_eq!(log, vec![100, 200, 300, 100, 400, 400]);
+
+    let mut log = vec![];
+    let mut v = vec![1, 2, 3, 4];
+    let mut next = |i: usize| {
+        log.push(i);
+        i
+    };
+    permute!((mut v[next(3)], mut v[next(0)]), (mut v[next(2)], mut v[next(1)]));
+    assert_eq!(v, vec![4, 3, 2, 1]);
+    assert_eq!(log, vec![3, 0, 2, 1]);
+}
+
+#[test]
+fn test_permute_restore() {
+    let a = RefCell::new(String::from("a"));
+    let b = RefCell::new(String::from("b"));
+    let c = RefCell::new(String::from("c"));
+    let d = RefCell::new(String::from("d"));
+    let guard = d.borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| permute!((&a, &b), (&c, &d))));
+    assert!(result.is_err());
+    drop(guard);
+    // The first cycle is kept; the second one is rolled back.
+    assert_eq!([a, b, c, d].map(RefCell::into_inner), ["b", "a", "c", "d"]);
+}
@@ -1,6 +1,6 @@
 use std::cell::{Cell, RefCell};
 
-use omniswap::Replace;
+use omniswap::{Replace, TryReplace};
 
 #[test]
 fn test_replace_mut() {
@@ -27,3 +27,40 @@ fn test_replace_ref_cell() {
     let place = place.into_inner();
     assert_eq!(place, 84);
 }
+
+#[test]
+fn test_try_replace_mut() {
+    let mut place = 42;
+    let taken = TryReplace::try_replace(&mut place, 84).unwrap();
+    assert_eq!(taken, 42);
+    assert_eq!(place, 84);
+}
+
+#[test]
+fn test_try_replace_cell() {
+    let place = Cell::new(42);
+    let taken = TryReplace::try_replace(&place, 84).unwrap();
+    assert_eq!(taken, 42);
+    let place = place.into_inner();
+    assert_eq!(place, 84);
+}
+
+#[test]
+fn test_try_replace_ref_cell() {
+    {
+        let place = RefCell::new(42);
+        let taken = TryReplace::try_replace(&place, 84).unwrap();
+        assert_eq!(taken, 42);
+        let place = place.into_inner();
+        assert_eq!(place, 84);
+    }
+    {
+        let place = RefCell::new(42);
+        let guard = place.borrow();
+        let (_, value) = TryReplace::try_replace(&place, 84).unwrap_err();
+        assert_eq!(value, 84);
+        drop(guard);
+        let place = place.into_inner();
+        assert_eq!(place, 42);
+    }
+}
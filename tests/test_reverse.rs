@@ -0,0 +1,83 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::reverse;
+
+#[test]
+fn test_reverse() {
+    let mut a = 'a';
+    let mut b = 'b';
+    let mut c = 'c';
+    let mut d = 'd';
+    reverse!(&mut a, &mut b, &mut c, &mut d);
+    assert_eq!((a, b, c, d), ('d', 'c', 'b', 'a'));
+
+    let mut v = vec![1, 2, 3, 4, 5];
+    reverse!(mut v[0], mut v[1], mut v[2], mut v[3], mut v[4],);
+    assert_eq!(v, [5, 4, 3, 2, 1]);
+
+    let mut v = vec![String::from("x")];
+    reverse!(mut v[0]);
+    assert_eq!(v, ["x"]);
+
+    let x = RefCell::new(vec![1]);
+    let y = Cell::new(vec![2]);
+    let mut z = vec![3];
+    reverse!(&x, &y, &mut z);
+    assert_eq!(
+        (x.into_inner(), y.into_inner(), z),
+        (vec![3], vec![2], vec![1])
+    );
+
+    // Cells are swapped directly.
+    let x = Cell::new(Box::new(1));
+    let y = Cell::new(Box::new(2));
+    reverse!(&x, &y);
+    assert_eq!((*x.into_inner(), *y.into_inner()), (2, 1));
+}
+
+#[test]
+fn test_reverse_eval_order() {
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    let mut z = 3;
+    reverse!(
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        },
+        {
+            log.push(300);
+            &mut z
+        }
+    );
+    assert_eq!((x, y, z), (3, 2, 1));
+    assert_eq!(log, vec![100, 300, 100, 200]);
+
+    let mut log = vec![];
+    let mut v = vec![1, 2, 3, 4];
+    let mut next = |i: usize| {
+        log.push(i);
+        i
+    };
+    reverse!(mut v[next(0)], mut v[next(1)], mut v[next(2)], mut v[next(3)]);
+    assert_eq!(v, vec![4, 3, 2, 1]);
+    assert_eq!(log, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_reverse_partial() {
+    let places = ["a", "b", "c", "d"].map(|s| RefCell::new(String::from(s)));
+    let guard = places[2].borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        reverse!(&places[0], &places[1], &places[2], &places[3]);
+    }));
+    assert!(result.is_err());
+    drop(guard);
+    assert_eq!(places.map(RefCell::into_inner), ["d", "b", "c", "a"]);
+}
@@ -0,0 +1,207 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::{rotate, rotate_left, rotate_right, try_rotate, TryError};
+
+#[test]
+fn test_rotate_left_right() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    let mut z = String::from("z");
+    rotate_left!(&mut x, &mut y, &mut z);
+    assert_eq!((x.as_str(), y.as_str(), z.as_str()), ("y", "z", "x"));
+    rotate_right!(&mut x, &mut y, &mut z);
+    assert_eq!((x.as_str(), y.as_str(), z.as_str()), ("x", "y", "z"));
+
+    // Cells are still swapped directly.
+    let a = [Cell::new(Box::new(1)), Cell::new(Box::new(2))];
+    let b = Cell::new(Box::new(3));
+    rotate_left!(&a[0], &a[1], &b);
+    let values = [a[0].take(), a[1].take(), b.take()].map(|b| *b);
+    assert_eq!(values, [2, 3, 1]);
+
+    let mut a = [1, 2, 3];
+    rotate_left!(mut a[0], mut a[1], mut a[2],);
+    assert_eq!(a, [2, 3, 1]);
+}
+
+#[test]
+fn test_rotate_by() {
+    for k in 0..8 {
+        let mut a = [0, 1, 2, 3, 4, 5];
+        rotate!(k; mut a[0], mut a[1], mut a[2], mut a[3], mut a[4], mut a[5]);
+        let mut expected = [0, 1, 2, 3, 4, 5];
+        expected.rotate_right(k % 6);
+        assert_eq!(a, expected, "k = {}", k);
+
+        let mut a = [0, 1, 2, 3, 4, 5].map(|i| i.to_string());
+        rotate_left!(k; mut a[0], mut a[1], mut a[2], mut a[3], mut a[4], mut a[5]);
+        let mut expected = [0, 1, 2, 3, 4, 5].map(|i| i.to_string());
+        expected.rotate_left(k % 6);
+        assert_eq!(a, expected, "k = {}", k);
+
+        let mut a = [0, 1, 2, 3, 4].map(|i| vec![i]);
+        rotate_right!(k; mut a[0], mut a[1], mut a[2], mut a[3], mut a[4]);
+        let mut expected = [0, 1, 2, 3, 4].map(|i| vec![i]);
+        expected.rotate_right(k % 5);
+        assert_eq!(a, expected, "k = {}", k);
+    }
+
+    let x = RefCell::new(String::from("x"));
+    let y = Cell::new(String::from("y"));
+    let mut z = String::from("z");
+    rotate!(2; &x, &y, &mut z);
+    assert_eq!(
+        (x.into_inner(), y.into_inner(), z),
+        (String::from("y"), String::from("z"), String::from("x"))
+    );
+}
+
+#[test]
+fn test_rotate_by_eval_order() {
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    let mut z = 3;
+    let mut w = 4;
+    rotate!(
+        {
+            log.push(0);
+            2
+        };
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        },
+        {
+            log.push(300);
+            &mut z
+        },
+        {
+            log.push(400);
+            &mut w
+        }
+    );
+    assert_eq!((x, y, z, w), (3, 4, 1, 2));
+    assert_eq!(log, vec![0, 100, 300, 100, 200, 400, 200]);
+
+    let mut log = vec![];
+    let mut x = 1;
+    rotate!(
+        {
+            log.push(0);
+            3
+        };
+        {
+            log.push(100);
+            &mut x
+        }
+    );
+    assert_eq!(x, 1);
+    assert_eq!(log, vec![0]);
+
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    let mut z = 3;
+    rotate_left!(
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        },
+        {
+            log.push(300);
+            &mut z
+        }
+    );
+    assert_eq!((x, y, z), (2, 3, 1));
+    assert_eq!(log, vec![100, 300, 200, 100]);
+}
+
+#[test]
+fn test_rotate_by_restore() {
+    let places = ["a", "b", "c", "d", "e", "f"].map(|s| RefCell::new(String::from(s)));
+    for locked in 0..6 {
+        let guard = places[locked].borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(2; &places[0], &places[1], &places[2], &places[3], &places[4], &places[5]);
+        }));
+        assert!(result.is_err());
+        drop(guard);
+        let values = places.each_ref().map(|p| p.borrow().clone());
+        assert_eq!(
+            values,
+            ["a", "b", "c", "d", "e", "f"],
+            "locked = {}",
+            locked
+        );
+    }
+}
+
+#[test]
+fn test_rotate_by_panic_in_argument() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    let mut v = Vec::from([String::from("v")]);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rotate!(1; &mut x, &mut y, &mut v[5]);
+    }));
+    assert!(result.is_err());
+    assert_eq!((x.as_str(), y.as_str(), v[0].as_str()), ("x", "y", "v"));
+
+    for panicking in 0..6 {
+        let mut v = ["a", "b", "c", "d", "e", "f"].map(String::from).to_vec();
+        let index = |i: usize| if i == panicking { 9 } else { i };
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(2; &mut v[index(0)], &mut v[index(1)], &mut v[index(2)],
+                &mut v[index(3)], &mut v[index(4)], &mut v[index(5)]);
+        }));
+        assert!(result.is_err());
+        assert_eq!(
+            v,
+            ["a", "b", "c", "d", "e", "f"],
+            "panicking = {}",
+            panicking
+        );
+    }
+}
+
+#[test]
+fn test_rotate_by_question_mark_in_index() {
+    fn rotate_at(v: &mut [i32], i: Option<usize>) -> Option<()> {
+        rotate!(2; mut v[0], mut v[i?], mut v[2]);
+        Some(())
+    }
+    let mut v = [1, 2, 3];
+    assert_eq!(rotate_at(&mut v, None), None);
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(rotate_at(&mut v, Some(1)), Some(()));
+    assert_eq!(v, [2, 3, 1]);
+}
+
+#[test]
+fn test_try_rotate_by() {
+    let places = ["a", "b", "c", "d"].map(|s| RefCell::new(String::from(s)));
+    let guard = places[3].borrow_mut();
+    let result = try_rotate!(2; &places[0], &places[1], &places[2], &places[3]);
+    assert!(matches!(result, Err(TryError::BorrowMut(_))));
+    drop(guard);
+    let guard = places[2].borrow();
+    let result = try_rotate!(1; &places[0], &places[1], &places[2], &places[3]);
+    assert!(matches!(result, Err(TryError::BorrowMut(_))));
+    drop(guard);
+    let values = places.each_ref().map(|p| p.borrow().clone());
+    assert_eq!(values, ["a", "b", "c", "d"]);
+
+    assert!(try_rotate!(3; &places[0], &places[1], &places[2], &places[3]).is_ok());
+    let values = places.map(RefCell::into_inner);
+    assert_eq!(values, ["b", "c", "d", "a"]);
+}
@@ -0,0 +1,83 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::shift;
+
+mod common;
+
+use common::Handle;
+
+#[test]
+fn test_shift() {
+    // No `Sentinel`, `Default` or `Clone` is needed.
+    let mut x = Handle(1);
+    let mut y = Handle(2);
+    let mut z = Handle(3);
+    let dropped = shift!(Handle(0) => &mut x, &mut y, &mut z);
+    assert_eq!(
+        (x, y, z, dropped),
+        (Handle(0), Handle(1), Handle(2), Handle(3))
+    );
+
+    let x = Cell::new(Handle(1));
+    let y = RefCell::new(Handle(2));
+    let mut z = Handle(3);
+    let dropped = shift!(Handle(0) => &x, &y, &mut z,);
+    assert_eq!(
+        (x.into_inner(), y.into_inner(), z, dropped),
+        (Handle(0), Handle(1), Handle(2), Handle(3))
+    );
+
+    let mut a = [1, 2, 3];
+    assert_eq!(shift!(0 => mut a[0], mut a[1], mut a[2]), 3);
+    assert_eq!(a, [0, 1, 2]);
+
+    assert_eq!(shift!(42 =>), 42);
+}
+
+#[test]
+fn test_shift_eval_order() {
+    let mut log = vec![];
+    let mut v = vec![1, 2, 3];
+    let mut next = |i: usize| {
+        log.push(i);
+        i
+    };
+    let dropped = shift!(
+        {
+            next(100);
+            0
+        } => mut v[next(2)],
+        mut v[next(0)]
+    );
+    assert_eq!(dropped, 1);
+    assert_eq!(v, [3, 2, 0]);
+    assert_eq!(log, vec![100, 2, 0]);
+}
+
+#[test]
+fn test_shift_restore() {
+    let x = RefCell::new(String::from("x"));
+    let y = RefCell::new(String::from("y"));
+    let z = RefCell::new(String::from("z"));
+    let guard = z.borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        shift!(String::from("new") => &x, &y, &z);
+    }));
+    assert!(result.is_err());
+    drop(guard);
+    assert_eq!([x, y, z].map(RefCell::into_inner), ["x", "y", "z"]);
+}
+
+#[test]
+fn test_shift_panic_in_argument() {
+    let mut a = String::from("a");
+    let mut b = String::from("b");
+    let mut w = Vec::from([String::from("w")]);
+    let result = catch_unwind(AssertUnwindSafe(
+        || shift!(String::from("new") => &mut a, &mut b, &mut w[3]),
+    ));
+    assert!(result.is_err());
+    // The places already updated are not restored, and the old value of `b` is lost.
+    assert_eq!((a.as_str(), b.as_str(), w[0].as_str()), ("new", "a", "w"));
+}
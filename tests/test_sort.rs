@@ -0,0 +1,180 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::{sort2, sort3, sort_places};
+
+fn sort_array<const N: usize>(a: &mut [u8; N]) {
+    match a.as_mut_slice() {
+        [a0, a1] => sort_places!(&mut *a0, &mut *a1),
+        [a0, a1, a2] => sort_places!(&mut *a0, &mut *a1, &mut *a2),
+        [a0, a1, a2, a3] => sort_places!(&mut *a0, &mut *a1, &mut *a2, &mut *a3),
+        [a0, a1, a2, a3, a4] => sort_places!(&mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4),
+        [a0, a1, a2, a3, a4, a5] => {
+            sort_places!(&mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4, &mut *a5)
+        }
+        [a0, a1, a2, a3, a4, a5, a6] => {
+            sort_places!(&mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4, &mut *a5, &mut *a6)
+        }
+        [a0, a1, a2, a3, a4, a5, a6, a7] => sort_places!(
+            &mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4, &mut *a5, &mut *a6, &mut *a7
+        ),
+        [a0, a1, a2, a3, a4, a5, a6, a7, a8] => {
+            sort_places!(
+                &mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4, &mut *a5, &mut *a6, &mut *a7,
+                &mut *a8
+            )
+        }
+        [a0, a1, a2, a3, a4, a5, a6, a7, a8, a9] => {
+            sort_places!(
+                &mut *a0, &mut *a1, &mut *a2, &mut *a3, &mut *a4, &mut *a5, &mut *a6, &mut *a7,
+                &mut *a8, &mut *a9
+            )
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn check_all_binary<const N: usize>() {
+    // By the 0-1 principle, a network sorts everything if it sorts all sequences of 0 and 1.
+    for bits in 0..1u32 << N {
+        let mut a = [0u8; N];
+        for (i, x) in a.iter_mut().enumerate() {
+            *x = (bits >> i & 1) as u8;
+        }
+        let mut expected = a;
+        expected.sort();
+        sort_array(&mut a);
+        assert_eq!(a, expected, "bits = {:b}", bits);
+    }
+}
+
+#[test]
+fn test_sort_networks() {
+    check_all_binary::<2>();
+    check_all_binary::<3>();
+    check_all_binary::<4>();
+    check_all_binary::<5>();
+    check_all_binary::<6>();
+    check_all_binary::<7>();
+    check_all_binary::<8>();
+    check_all_binary::<9>();
+    check_all_binary::<10>();
+}
+
+#[test]
+fn test_sort2_sort3() {
+    let mut u = 5;
+    let mut v = 2;
+    sort2!(&mut u, &mut v);
+    assert_eq!((u, v), (2, 5));
+    sort2!(by |a: &i32, b: &i32| b.cmp(a); &mut u, &mut v,);
+    assert_eq!((u, v), (5, 2));
+
+    let x = Cell::new(3);
+    let y = RefCell::new(1);
+    let mut z = 2;
+    sort3!(&x, &y, &mut z);
+    assert_eq!((x.get(), y.into_inner(), z), (1, 2, 3));
+
+    let mut p = [String::from("c"), String::from("a"), String::from("b")];
+    sort3!(mut p[0], mut p[1], mut p[2]);
+    assert_eq!(p, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_sort_places_mixed() {
+    let x = RefCell::new(vec![3]);
+    let y = Cell::new(vec![1]);
+    let mut z = vec![2];
+    let mut w = [vec![0]];
+    sort_places!(&x, &y, &mut z, mut w[0]);
+    assert_eq!(
+        (x.into_inner(), y.into_inner(), z, w),
+        (vec![0], vec![1], vec![2], [vec![3]])
+    );
+
+    let mut a = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')];
+    sort_places!(by |x: &(i32, char), y: &(i32, char)| x.0.cmp(&y.0); mut a[0], mut a[1], mut a[2], mut a[3]);
+    assert_eq!(a.map(|x| x.0), [0, 0, 1, 1]);
+}
+
+#[test]
+fn test_sort_places_eval_order() {
+    let mut log = vec![];
+    let mut x = 2;
+    let mut y = 1;
+    sort2!(
+        by {
+            log.push(0);
+            |a: &i32, b: &i32| a.cmp(b)
+        };
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        }
+    );
+    assert_eq!((x, y), (1, 2));
+    assert_eq!(log, vec![0, 100, 200, 100, 200]);
+
+    let mut log = vec![];
+    let mut v = vec![3, 2, 1];
+    let mut next = |i: usize| {
+        log.push(i);
+        i
+    };
+    sort3!(mut v[next(0)], mut v[next(1)], mut v[next(2)]);
+    assert_eq!(v, vec![1, 2, 3]);
+    assert_eq!(log, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_sort_places_unavailable() {
+    let places = ["c", "b", "a"].map(|s| RefCell::new(String::from(s)));
+    let guard = places[1].borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        sort_places!(&places[0], &places[1], &places[2]);
+    }));
+    assert!(result.is_err());
+    drop(guard);
+    // The first exchange (0, 2) is kept; the values of the failed pair are put back.
+    assert_eq!(places.map(RefCell::into_inner), ["a", "b", "c"]);
+}
+
+#[test]
+fn test_sort_places_panic_in_argument() {
+    let mut s1 = String::from("s1");
+    let mut s2 = Vec::from([String::from("s2")]);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        sort_places!(&mut s1, &mut s2[0], &mut s2[7]);
+    }));
+    assert!(result.is_err());
+    assert_eq!((s1.as_str(), s2[0].as_str()), ("s1", "s2"));
+}
+
+#[test]
+fn test_sort_places_panic_in_comparator() {
+    let x = RefCell::new(String::from("c"));
+    let mut y = String::from("b");
+    let mut z = [String::from("a")];
+    let mut count = 0;
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        sort_places!(
+            by |a: &String, b: &String| {
+                count += 1;
+                assert!(count < 2, "comparator failed");
+                a.cmp(b)
+            };
+            &x, &mut y, mut z[0]
+        );
+    }));
+    assert!(result.is_err());
+    // The first exchange (0, 2) is kept; the values of the pair (0, 1) are put back.
+    assert_eq!(
+        (x.into_inner(), y, z),
+        ("a".into(), "b".into(), ["c".into()])
+    );
+}
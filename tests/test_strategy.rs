@@ -0,0 +1,116 @@
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::AtomicUsize;
+
+use omniswap::{swap_strategy, take_strategy, Sentinel, Strategy};
+
+#[derive(Debug, Clone, PartialEq)]
+struct Mesh(Vec<f32>);
+
+#[derive(Debug, Default)]
+struct WithSentinel(#[allow(dead_code)] Vec<f32>);
+
+impl Sentinel for WithSentinel {
+    fn sentinel() -> Self {
+        WithSentinel(vec![])
+    }
+}
+
+#[test]
+fn test_take_strategy() {
+    let mut x = 42;
+    assert_eq!(take_strategy!(&mut x), Strategy::Copy);
+    let mut x = WithSentinel(vec![1.0]);
+    assert_eq!(take_strategy!(&mut x), Strategy::Sentinel);
+    let mut x = String::from("a");
+    assert_eq!(take_strategy!(&mut x), Strategy::Default);
+    let mut x = Mesh(vec![1.0]);
+    assert_eq!(take_strategy!(&mut x,), Strategy::Clone);
+
+    assert_eq!(take_strategy!(&Cell::new(1)), Strategy::Copy);
+    assert_eq!(take_strategy!(&Cell::new(vec![1])), Strategy::Default);
+    assert_eq!(take_strategy!(&RefCell::new(Mesh(vec![]))), Strategy::Clone);
+    assert_eq!(take_strategy!(&AtomicUsize::new(1)), Strategy::Copy);
+
+    // The place is not accessed.
+    let x = RefCell::new(Mesh(vec![1.0]));
+    let _guard = x.borrow_mut();
+    assert_eq!(take_strategy!(&x), Strategy::Clone);
+}
+
+#[test]
+fn test_swap_strategy() {
+    let mut a = Mesh(vec![1.0]);
+    let mut b = Mesh(vec![2.0]);
+    assert_eq!(swap_strategy!(&mut a, &mut b), Strategy::Clone);
+    let mut v = [String::new(), String::new()];
+    assert_eq!(swap_strategy!(&mut v[0], &mut v[1]), Strategy::Default);
+
+    let a = Cell::new(Mesh(vec![1.0]));
+    let b = Cell::new(Mesh(vec![2.0]));
+    assert_eq!(swap_strategy!(&a, &b), Strategy::Direct);
+    let a = RefCell::new(Mesh(vec![1.0]));
+    assert_eq!(swap_strategy!(&a, &mut Mesh(vec![])), Strategy::Direct);
+}
+
+#[test]
+fn test_swap_strategy_eval() {
+    let mut log = vec![];
+    let mut x = 1;
+    let mut y = 2;
+    let strategy = swap_strategy!(
+        {
+            log.push(100);
+            &mut x
+        },
+        {
+            log.push(200);
+            &mut y
+        }
+    );
+    assert_eq!(strategy, Strategy::Copy);
+    assert_eq!(log, [100]);
+    assert_eq!((x, y), (1, 2));
+}
+
+#[test]
+fn test_swap_strategy_place_syntax() {
+    let mut a = [String::from("a"), String::from("b")];
+    assert_eq!(swap_strategy!(mut a[0], mut a[1]), Strategy::Default);
+    let mut i = 0;
+    let mut next = || {
+        i += 1;
+        i - 1
+    };
+    assert_eq!(
+        swap_strategy!(mut a[next()], &mut String::new(),),
+        Strategy::Default
+    );
+    assert_eq!(i, 1);
+    assert_eq!(a, ["a", "b"]);
+}
+
+#[test]
+fn test_swap_strategy_modifiers() {
+    let mut a = [Mesh(vec![1.0]), Mesh(vec![2.0])];
+    assert_eq!(swap_strategy!(@clone mut a[0], mut a[1]), Strategy::Clone);
+    let mut b = [WithSentinel(vec![1.0]), WithSentinel(vec![2.0])];
+    assert_eq!(
+        swap_strategy!(@sentinel mut b[0], mut b[1]),
+        Strategy::Sentinel
+    );
+    assert_eq!(
+        swap_strategy!(@default mut b[0], mut b[1]),
+        Strategy::Default
+    );
+    let mut c = [1, 2];
+    assert_eq!(swap_strategy!(@copy mut c[0], mut c[1]), Strategy::Copy);
+    assert_eq!(
+        swap_strategy!(with Mesh(vec![]); mut a[0], mut a[1]),
+        Strategy::Sentinel
+    );
+
+    // The cell is not swapped directly if the strategy is pinned.
+    let x = RefCell::new(Mesh(vec![1.0]));
+    let y = RefCell::new(Mesh(vec![2.0]));
+    assert_eq!(swap_strategy!(@clone &x, &y), Strategy::Clone);
+}
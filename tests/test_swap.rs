@@ -1,6 +1,11 @@
 use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
-use omniswap::{rotate, swap};
+use omniswap::{rotate, swap, try_rotate, try_swap, Replace, TryError};
+
+mod common;
+
+use common::{Handle, Token};
 
 #[test]
 fn test_swap() {
@@ -59,6 +64,23 @@ fn test_swap_noncopy() {
     assert_eq!((x, y), (vec![4, 5], vec![1, 2, 3]));
 }
 
+#[test]
+fn test_swap_sentinel() {
+    {
+        let mut a = [[Token(1), Token(2)], [Token(3), Token(4)]];
+        swap!(&mut a[0][0], &mut a[1][1]);
+        assert_eq!(a, [[Token(4), Token(2)], [Token(3), Token(1)]]);
+    }
+    {
+        let x = Cell::new(Token(1));
+        let y = RefCell::new(Token(2));
+        swap!(&x, &y);
+        let x = x.into_inner();
+        let y = y.into_inner();
+        assert_eq!((x, y), (Token(2), Token(1)));
+    }
+}
+
 #[test]
 fn test_swap_cell() {
     {
@@ -111,6 +133,22 @@ fn test_swap_ref_cell() {
     }
 }
 
+#[test]
+fn test_swap_indexed_cell_refs() {
+    // Without the `mut` marker, `cells[0]` is an ordinary expression of type `&Cell<i32>`.
+    let a = Cell::new(1);
+    let b = Cell::new(2);
+    let mut cells = [&a, &b];
+    swap!(cells[0], cells[1]);
+    assert_eq!((a.get(), b.get()), (2, 1));
+    assert!(std::ptr::eq(cells[0], &a) && std::ptr::eq(cells[1], &b));
+
+    // With the marker, the array elements themselves are swapped.
+    swap!(mut cells[0], mut cells[1]);
+    assert_eq!((a.get(), b.get()), (2, 1));
+    assert!(std::ptr::eq(cells[0], &b) && std::ptr::eq(cells[1], &a));
+}
+
 #[test]
 fn test_swap_eval_order() {
     let mut log = vec![];
@@ -128,6 +166,44 @@ fn test_swap_eval_order() {
     );
     assert_eq!((x, y), (84, 42));
     assert_eq!(log, vec![100, 200, 100]);
+
+    let mut log = vec![];
+    let mut a = [[1, 2], [3, 4]];
+    swap!(
+        mut a[{
+            log.push(100);
+            0
+        }][{
+            log.push(101);
+            1
+        }],
+        mut a[{
+            log.push(200);
+            1
+        }][{
+            log.push(201);
+            0
+        }]
+    );
+    assert_eq!(a, [[1, 3], [2, 4]]);
+    assert_eq!(log, vec![100, 101, 200, 201]);
+
+    // Mixed with an ordinary reference.
+    let mut log = vec![];
+    let mut a = [1, 2];
+    let mut y = 3;
+    swap!(
+        mut a[{
+            log.push(100);
+            1
+        }],
+        {
+            log.push(200);
+            &mut y
+        },
+    );
+    assert_eq!((a, y), ([1, 3], 2));
+    assert_eq!(log, vec![100, 200]);
 }
 
 #[test]
@@ -225,4 +301,715 @@ fn test_swap_cycle_eval_order() {
         assert_eq!((x, y, z), (3, 1, 2));
         assert_eq!(log, vec![100, 200, 300, 100]);
     }
+
+    {
+        let mut log = vec![];
+        let mut v = vec![1, 2, 3, 4];
+        let mut next = |i: usize| {
+            log.push(i);
+            i
+        };
+        rotate!(mut v[next(3)], mut v[next(0)], mut v[next(2)],);
+        assert_eq!(v, [4, 2, 1, 3]);
+        assert_eq!(log, vec![3, 0, 2]);
+    }
+
+    {
+        // The index may refer to the base.
+        let mut v = vec![1, 2, 3];
+        rotate!(mut v[0], mut v[v.len() - 1]);
+        assert_eq!(v, [3, 2, 1]);
+    }
+}
+
+#[test]
+fn test_swap_panic_restore() {
+    {
+        // The second place is unavailable
+        let x = RefCell::new(String::from("x"));
+        let y = RefCell::new(String::from("y"));
+        let guard = y.borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| swap!(&x, &y)));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!((x.into_inner(), y.into_inner()), ("x".into(), "y".into()));
+    }
+    {
+        // The first place is unavailable when putting the value back
+        let x = RefCell::new(42);
+        let y = RefCell::new(84);
+        let guard = x.borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| swap!(&x, &y)));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!((x.into_inner(), y.into_inner()), (42, 84));
+    }
+    {
+        let mut x = String::from("x");
+        let y = RefCell::new(String::from("y"));
+        let guard = y.borrow_mut();
+        let result = catch_unwind(AssertUnwindSafe(|| swap!(&mut x, &y)));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!((x, y.into_inner()), ("x".into(), "y".into()));
+    }
+}
+
+#[test]
+fn test_swap_cycle_panic_restore() {
+    for borrowed in 0..4 {
+        let places = [1, 2, 3, 4].map(RefCell::new);
+        let guard = places[borrowed].borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(&places[0], &places[1], &places[2], &places[3])
+        }));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!(places.map(RefCell::into_inner), [1, 2, 3, 4]);
+    }
+    for borrowed in 0..4 {
+        let places = ["a", "b", "c", "d"].map(|s| RefCell::new(String::from(s)));
+        let guard = places[borrowed].borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(&places[0], &places[1], &places[2], &places[3])
+        }));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!(places.map(RefCell::into_inner), ["a", "b", "c", "d"]);
+    }
+    for borrowed in 1..4 {
+        let mut x = String::from("a");
+        let places = ["b", "c", "d"].map(|s| RefCell::new(String::from(s)));
+        let guard = places[borrowed - 1].borrow();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(&mut x, &places[0], &places[1], &places[2])
+        }));
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!(x, "a");
+        assert_eq!(places.map(RefCell::into_inner), ["b", "c", "d"]);
+    }
+}
+
+#[test]
+fn test_swap_cycle_panic_restore_eval_order() {
+    let mut log = vec![];
+    let x = RefCell::new(1);
+    let y = RefCell::new(2);
+    let z = RefCell::new(3);
+    let guard = z.borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rotate!(
+            {
+                log.push(100);
+                &x
+            },
+            {
+                log.push(200);
+                &y
+            },
+            {
+                log.push(300);
+                &z
+            }
+        )
+    }));
+    assert!(result.is_err());
+    drop(guard);
+    assert_eq!((x.into_inner(), y.into_inner(), z.into_inner()), (1, 2, 3));
+    assert_eq!(log, vec![100, 200, 300, 200, 100]);
+}
+
+#[test]
+fn test_swap_panic_in_argument() {
+    // Panics from the arguments themselves leave the sentinel behind.
+    let mut x = String::from("x");
+    let mut v = Vec::<String>::new();
+    let result = catch_unwind(AssertUnwindSafe(|| swap!(&mut x, &mut v[0])));
+    assert!(result.is_err());
+    assert_eq!(x, "");
+}
+
+/// Counts the evaluations of the arguments, and panics at the `target`-th one.
+struct PanicAt {
+    count: Cell<usize>,
+    target: usize,
+}
+
+impl PanicAt {
+    fn new(target: usize) -> Self {
+        PanicAt {
+            count: Cell::new(0),
+            target,
+        }
+    }
+
+    fn tick(&self) {
+        let count = self.count.get();
+        self.count.set(count + 1);
+        if count == self.target {
+            panic!("argument {}", count);
+        }
+    }
+}
+
+#[test]
+fn test_rotate_panic_in_each_argument() {
+    // The arguments are evaluated 5 times in total: the first one is evaluated again at the end.
+    // The value being moved is dropped, and the places already updated keep their new values.
+    let expected = [
+        ["w", "x", "y", "v"],
+        ["", "x", "y", "v"],
+        ["", "w", "y", "v"],
+        ["", "w", "x", "v"],
+        ["", "w", "x", "y"],
+        ["v", "w", "x", "y"],
+    ];
+    for (target, expected) in expected.into_iter().enumerate() {
+        let p = PanicAt::new(target);
+        let mut w = String::from("w");
+        let x = Cell::new(String::from("x"));
+        let y = RefCell::new(String::from("y"));
+        let mut v = vec![String::from("v")];
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(
+                {
+                    p.tick();
+                    &mut w
+                },
+                {
+                    p.tick();
+                    &x
+                },
+                {
+                    p.tick();
+                    &y
+                },
+                {
+                    p.tick();
+                    &mut v[0]
+                }
+            )
+        }));
+        assert_eq!(result.is_err(), target < 5, "target = {}", target);
+        assert_eq!(
+            [w, x.into_inner(), y.into_inner(), v.remove(0)],
+            expected,
+            "target = {}",
+            target
+        );
+    }
+}
+
+#[test]
+fn test_rotate_cell_panic_in_each_argument() {
+    // The first place is swapped directly with each of the others,
+    // so the swaps done so far are kept and nothing is lost.
+    let expected = [
+        ["x", "w", "y"],
+        ["x", "w", "y"],
+        ["w", "x", "y"],
+        ["y", "x", "w"],
+        ["y", "x", "w"],
+    ];
+    for (target, expected) in expected.into_iter().enumerate() {
+        let p = PanicAt::new(target);
+        let x = RefCell::new(String::from("x"));
+        let mut w = String::from("w");
+        let y = Cell::new(String::from("y"));
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            rotate!(
+                {
+                    p.tick();
+                    &x
+                },
+                {
+                    p.tick();
+                    &mut w
+                },
+                {
+                    p.tick();
+                    &y
+                }
+            )
+        }));
+        assert_eq!(result.is_err(), target < 4, "target = {}", target);
+        assert_eq!(
+            [x.into_inner(), w, y.into_inner()],
+            expected,
+            "target = {}",
+            target
+        );
+    }
+}
+
+#[test]
+fn test_try_rotate_panic_in_each_argument() {
+    // The first value is copied out, so the first place keeps its value.
+    let expected = [
+        (1, 2, 3, 4),
+        (1, 2, 3, 4),
+        (1, 1, 3, 4),
+        (1, 1, 2, 4),
+        (1, 1, 2, 3),
+    ];
+    for (target, expected) in expected.into_iter().enumerate() {
+        let p = PanicAt::new(target);
+        let mut w = 1;
+        let x = Cell::new(2);
+        let y = RefCell::new(3);
+        let mut v = [4];
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            try_rotate!(
+                {
+                    p.tick();
+                    &mut w
+                },
+                {
+                    p.tick();
+                    &x
+                },
+                {
+                    p.tick();
+                    &y
+                },
+                {
+                    p.tick();
+                    &mut v[0]
+                }
+            )
+        }));
+        assert!(result.is_err(), "target = {}", target);
+        assert_eq!(
+            (w, x.get(), y.into_inner(), v[0]),
+            expected,
+            "target = {}",
+            target
+        );
+    }
+}
+
+#[test]
+fn test_swap_question_mark_in_argument() {
+    // The arguments are evaluated in place, so `?` returns from the enclosing function.
+    fn swap_at(v: &mut [i32], i: Option<usize>) -> Option<()> {
+        swap!(&mut v[i?], &mut v[0]);
+        Some(())
+    }
+    let mut v = [1, 2, 3];
+    assert_eq!(swap_at(&mut v, Some(2)), Some(()));
+    assert_eq!(v, [3, 2, 1]);
+    assert_eq!(swap_at(&mut v, None), None);
+    assert_eq!(v, [3, 2, 1]);
+
+    fn try_swap_at(v: &[RefCell<i32>], i: Option<usize>) -> Option<Result<(), TryError>> {
+        Some(try_swap!(&v[0], &v[i?]))
+    }
+    let v = [1, 2].map(RefCell::new);
+    assert!(matches!(try_swap_at(&v, Some(1)), Some(Ok(()))));
+    assert!(try_swap_at(&v, None).is_none());
+    assert_eq!(v.map(RefCell::into_inner), [2, 1]);
+}
+
+#[test]
+fn test_swap_moved_mut_argument() {
+    // A `&mut` binding can be moved into the last place, which is evaluated at most once.
+    fn swap_into(b: &mut String) -> String {
+        let mut x = String::from("x");
+        swap!(&mut x, b);
+        x
+    }
+    let mut y = String::from("y");
+    assert_eq!(swap_into(&mut y), "y");
+    assert_eq!(y, "x");
+
+    fn rotate_into(b: &mut i32, c: &mut i32) {
+        let mut x = 0;
+        // The other arguments are evaluated again on failure, so they are reborrowed.
+        rotate!(&mut x, &mut *b, c);
+    }
+    let (mut y, mut z) = (1, 2);
+    rotate_into(&mut y, &mut z);
+    assert_eq!((y, z), (0, 1));
+}
+
+#[test]
+fn test_rotate_failed_place_not_evaluated_again() {
+    // The swap with the second place fails because its argument borrowed the first place.
+    // The second place is not evaluated again, and the first place is evaluated only twice,
+    // as in a successful rotation.
+    let mut log = vec![];
+    let x = RefCell::new(String::from("x"));
+    let mut y = String::from("y");
+    let mut guard = None;
+    let result = try_rotate!(
+        {
+            log.push(100);
+            &x
+        },
+        {
+            log.push(200);
+            guard = Some(x.borrow());
+            &mut y
+        }
+    );
+    assert!(matches!(result, Err(TryError::BorrowMut(_))));
+    drop(guard);
+    assert_eq!(log, vec![100, 200, 100]);
+    assert_eq!((x.into_inner(), y), (String::from("x"), String::from("y")));
+}
+
+/// A place that implements [`Replace`] but not [`omniswap::TryReplace`].
+struct Register<'a, T>(&'a RefCell<T>);
+
+impl<T> Replace<T> for &Register<'_, T> {
+    fn replace(self, value: T) -> T {
+        std::mem::replace(&mut *self.0.borrow_mut(), value)
+    }
+}
+
+#[test]
+fn test_swap_replace_only() {
+    let mut x = String::from("x");
+    let r = RefCell::new(String::from("r"));
+    let reg = Register(&r);
+    swap!(&mut x, &reg);
+    assert_eq!((x.as_str(), r.borrow().as_str()), ("r", "x"));
+
+    let mut y = String::from("y");
+    rotate!(&mut x, &reg, &mut y);
+    assert_eq!(
+        (x.as_str(), r.borrow().as_str(), y.as_str()),
+        ("y", "r", "x")
+    );
+}
+
+#[test]
+fn test_swap_replace_only_after_cell() {
+    // The value of the cell is taken out as in `take!`, since it cannot be swapped directly.
+    let x = Cell::new(1);
+    let r = RefCell::new(2);
+    swap!(&x, &Register(&r));
+    assert_eq!((x.get(), *r.borrow()), (2, 1));
+
+    let x = Cell::new(String::from("x"));
+    let r = RefCell::new(String::from("r"));
+    swap!(&x, &Register(&r));
+    assert_eq!((x.into_inner(), r.into_inner()), ("r".into(), "x".into()));
+
+    let x = RefCell::new(String::from("x"));
+    let r = RefCell::new(String::from("r"));
+    let mut y = String::from("y");
+    rotate!(&x, &Register(&r), &mut y);
+    assert_eq!(
+        (x.into_inner(), r.into_inner(), y),
+        ("y".into(), "x".into(), "r".into())
+    );
+
+    // The cell is still checked for borrows.
+    let x = RefCell::new(String::from("x"));
+    let r = RefCell::new(String::from("r"));
+    let guard = x.borrow();
+    assert!(try_swap!(&x, &Register(&r)).is_err());
+    drop(guard);
+    assert_eq!((x.into_inner(), r.into_inner()), ("x".into(), "r".into()));
+
+    // The failures of a place implementing `TryReplace` are reported,
+    // and the value taken out of the cell is put back.
+    let x = Cell::new(String::from("x"));
+    let r = RefCell::new(String::from("r"));
+    let guard = r.borrow();
+    assert!(try_swap!(&x, &Slot(&r)).is_err());
+    drop(guard);
+    assert_eq!((x.into_inner(), r.into_inner()), ("x".into(), "r".into()));
+}
+
+/// A place that implements [`omniswap::TryReplace`].
+struct Slot<'a, T>(&'a RefCell<T>);
+
+impl<T> Replace<T> for &Slot<'_, T> {
+    fn replace(self, value: T) -> T {
+        self.0.replace(value)
+    }
+}
+
+impl<T> omniswap::TryReplace<T> for &Slot<'_, T> {
+    type Error = TryError;
+    fn try_replace(self, value: T) -> Result<T, (TryError, T)> {
+        match self.0.try_borrow_mut() {
+            Ok(mut r) => Ok(std::mem::replace(&mut *r, value)),
+            Err(e) => Err((e.into(), value)),
+        }
+    }
+}
+
+#[test]
+fn test_try_swap() {
+    {
+        let mut x = 42;
+        let mut y = 84;
+        try_swap!(&mut x, &mut y).unwrap();
+        assert_eq!((x, y), (84, 42));
+    }
+    {
+        let x = Cell::new(42);
+        let y = RefCell::new(84);
+        try_swap!(&x, &y,).unwrap();
+        assert_eq!((x.into_inner(), y.into_inner()), (84, 42));
+    }
+    {
+        let x = RefCell::new(String::from("x"));
+        let y = RefCell::new(String::from("y"));
+        let guard = x.borrow();
+        let result = try_swap!(&x, &y);
+        assert!(matches!(result, Err(TryError::BorrowMut(_))));
+        drop(guard);
+        let guard = y.borrow();
+        let result = try_swap!(&x, &y);
+        assert!(matches!(result, Err(TryError::BorrowMut(_))));
+        drop(guard);
+        assert_eq!((x.into_inner(), y.into_inner()), ("x".into(), "y".into()));
+    }
+}
+
+#[test]
+fn test_try_swap_cycle() {
+    {
+        let mut x = 42;
+        try_rotate!(&mut x).unwrap();
+        try_rotate!(&mut x,).unwrap();
+        assert_eq!(x, 42);
+    }
+    {
+        let places = [1, 2, 3, 4].map(RefCell::new);
+        try_rotate!(&places[0], &places[1], &places[2], &places[3]).unwrap();
+        try_rotate!(&places[0], &places[1], &places[2], &places[3],).unwrap();
+        assert_eq!(places.map(RefCell::into_inner), [3, 4, 1, 2]);
+    }
+    for borrowed in 0..4 {
+        let places = [1, 2, 3, 4].map(RefCell::new);
+        let guard = places[borrowed].borrow();
+        let result = try_rotate!(&places[0], &places[1], &places[2], &places[3]);
+        assert!(matches!(result, Err(TryError::BorrowMut(_))));
+        drop(guard);
+        assert_eq!(places.map(RefCell::into_inner), [1, 2, 3, 4]);
+    }
+    for borrowed in 0..4 {
+        let places = [1, 2, 3, 4].map(RefCell::new);
+        let guard = places[borrowed].borrow_mut();
+        let result = try_rotate!(&places[0], &places[1], &places[2], &places[3]);
+        if borrowed == 0 {
+            assert!(matches!(result, Err(TryError::Borrow(_))));
+        } else {
+            assert!(matches!(result, Err(TryError::BorrowMut(_))));
+        }
+        drop(guard);
+        assert_eq!(places.map(RefCell::into_inner), [1, 2, 3, 4]);
+    }
+    for borrowed in 1..4 {
+        let mut x = String::from("a");
+        let places = ["b", "c", "d"].map(|s| RefCell::new(String::from(s)));
+        let guard = places[borrowed - 1].borrow();
+        let result = try_rotate!(&mut x, &places[0], &places[1], &places[2]);
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!(x, "a");
+        assert_eq!(places.map(RefCell::into_inner), ["b", "c", "d"]);
+    }
+}
+
+#[test]
+fn test_try_swap_cycle_eval_order() {
+    {
+        let mut log = vec![];
+        let x = RefCell::new(1);
+        let y = RefCell::new(2);
+        let z = RefCell::new(3);
+        let result = try_rotate!(
+            {
+                log.push(100);
+                &x
+            },
+            {
+                log.push(200);
+                &y
+            },
+            {
+                log.push(300);
+                &z
+            }
+        );
+        assert!(result.is_ok());
+        assert_eq!((x.into_inner(), y.into_inner(), z.into_inner()), (3, 1, 2));
+        assert_eq!(log, vec![100, 200, 300, 100]);
+    }
+    {
+        let mut log = vec![];
+        let x = RefCell::new(1);
+        let y = RefCell::new(2);
+        let z = RefCell::new(3);
+        let guard = z.borrow();
+        let result = try_rotate!(
+            {
+                log.push(100);
+                &x
+            },
+            {
+                log.push(200);
+                &y
+            },
+            {
+                log.push(300);
+                &z
+            }
+        );
+        assert!(result.is_err());
+        drop(guard);
+        assert_eq!((x.into_inner(), y.into_inner(), z.into_inner()), (1, 2, 3));
+        assert_eq!(log, vec![100, 200, 300, 200, 100]);
+    }
+}
+
+#[test]
+fn test_swap_cell_no_sentinel() {
+    type F = Box<dyn Fn() -> i32>;
+    {
+        let x: Cell<F> = Cell::new(Box::new(|| 1));
+        let y: Cell<F> = Cell::new(Box::new(|| 2));
+        swap!(&x, &y);
+        swap!(&x, &x);
+        assert_eq!((x.into_inner()(), y.into_inner()()), (2, 1));
+    }
+    {
+        let x: RefCell<F> = RefCell::new(Box::new(|| 1));
+        let y: RefCell<F> = RefCell::new(Box::new(|| 2));
+        let z: Cell<F> = Cell::new(Box::new(|| 3));
+        let mut w: F = Box::new(|| 4);
+        rotate!(&x, &y, &z, &mut w);
+        swap!(&x, &x);
+        assert_eq!(
+            (x.into_inner()(), y.into_inner()(), z.into_inner()(), w()),
+            (4, 1, 2, 3)
+        );
+    }
+}
+
+#[test]
+fn test_swap_cell_no_sentinel_restore() {
+    let places = ["a", "b", "c"].map(|s| RefCell::new(Box::new(s) as Box<dyn AsRef<str>>));
+    let guard = places[2].borrow();
+    let result = try_rotate!(&places[0], &places[1], &places[2]);
+    assert!(matches!(result, Err(TryError::BorrowMut(_))));
+    drop(guard);
+    let values = places.map(|p| p.into_inner().as_ref().as_ref().to_owned());
+    assert_eq!(values, ["a", "b", "c"]);
+}
+
+#[test]
+fn test_swap_cell_copy_fallback() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let x = Cell::new(1);
+    let y = AtomicU32::new(2);
+    swap!(&x, &y);
+    assert_eq!((x.get(), y.load(Ordering::SeqCst)), (2, 1));
+
+    let x = RefCell::new(3);
+    swap!(&x, &y);
+    assert_eq!((x.into_inner(), y.into_inner()), (1, 3));
+}
+
+#[test]
+fn test_swap_with_sentinel() {
+    // No `Sentinel`, `Default` or `Clone` is needed.
+    let mut x = Handle(1);
+    let mut y = Handle(2);
+    let leftover = swap!(with Handle(0); &mut x, &mut y);
+    assert_eq!((x.0, y.0, leftover.0), (2, 1, 0));
+
+    let x = Cell::new(Handle(1));
+    let y = RefCell::new(Handle(2));
+    let leftover = swap!(with Handle(0); &x, &y);
+    assert_eq!((x.into_inner().0, y.into_inner().0, leftover.0), (2, 1, 0));
+
+    let mut a = [Handle(1), Handle(2)];
+    let leftover = swap!(with Handle(0); mut a[0], mut a[1]);
+    assert_eq!((a[0].0, a[1].0, leftover.0), (2, 1, 0));
+}
+
+#[test]
+fn test_rotate_with_sentinel() {
+    let mut buf = Vec::with_capacity(16);
+    buf.push(0);
+    buf.clear();
+    let mut x = vec![1];
+    let mut y = vec![2];
+    let z = RefCell::new(vec![3]);
+    let buf = rotate!(with buf; &mut x, &mut y, &z);
+    assert_eq!((x, y, z.into_inner()), (vec![3], vec![1], vec![2]));
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 16);
+}
+
+#[test]
+fn test_rotate_with_sentinel_restore() {
+    let x = RefCell::new(String::from("x"));
+    let y = RefCell::new(String::from("y"));
+    let z = RefCell::new(String::from("z"));
+    let guard = z.borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        rotate!(with String::from("-"); &x, &y, &z);
+    }));
+    assert!(result.is_err());
+    drop(guard);
+    assert_eq!([x, y, z].map(RefCell::into_inner), ["x", "y", "z"]);
+}
+
+#[test]
+fn test_swap_with_sentinel_eval_order() {
+    let mut log = Vec::new();
+    let mut x = 1;
+    let mut y = 2;
+    let leftover = swap!(
+        with {
+            log.push("sentinel");
+            0
+        };
+        {
+            log.push("x");
+            &mut x
+        },
+        {
+            log.push("y");
+            &mut y
+        }
+    );
+    assert_eq!((x, y, leftover), (2, 1, 0));
+    assert_eq!(log, ["sentinel", "x", "y", "x"]);
+}
+
+#[test]
+fn test_swap_forced_strategy() {
+    use std::rc::Rc;
+
+    let mut a = [Rc::new(1), Rc::new(2)];
+    swap!(@clone mut a[0], mut a[1]);
+    assert_eq!((*a[0], *a[1]), (2, 1));
+    assert_eq!((Rc::strong_count(&a[0]), Rc::strong_count(&a[1])), (1, 1));
+
+    let mut x = [1u8; 64];
+    let y = RefCell::new([2u8; 64]);
+    swap!(@sentinel &mut x, &y);
+    assert_eq!((x[0], y.into_inner()[0]), (2, 1));
+
+    let x = Cell::new(1);
+    let y = Cell::new(2);
+    swap!(@copy &x, &y);
+    assert_eq!((x.get(), y.get()), (2, 1));
+
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    let mut z = String::from("z");
+    rotate!(@default &mut x, &mut y, &mut z);
+    assert_eq!((x.as_str(), y.as_str(), z.as_str()), ("z", "x", "y"));
 }
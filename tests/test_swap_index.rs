@@ -0,0 +1,124 @@
+use omniswap::swap_index;
+
+#[test]
+fn test_swap_index() {
+    {
+        let mut a = [3, 5, 2, 6, 7];
+        swap_index!(a; [0], [2]);
+        assert_eq!(a, [2, 5, 3, 6, 7]);
+    }
+    {
+        let mut a = [3, 5, 2, 6, 7];
+        swap_index!(a; [0], [2],);
+        assert_eq!(a, [2, 5, 3, 6, 7]);
+    }
+    {
+        let mut a = [3, 5, 2, 6, 7];
+        swap_index!(a; [4], [4]);
+        assert_eq!(a, [3, 5, 2, 6, 7]);
+    }
+}
+
+#[test]
+fn test_swap_index_2d_array() {
+    {
+        let mut a = [[1, 2], [3, 4]];
+        swap_index!(a; [0][0], [0][0]);
+        assert_eq!(a, [[1, 2], [3, 4]]);
+    }
+    {
+        let mut a = [[1, 2], [3, 4]];
+        swap_index!(a; [0][0], [0][1]);
+        assert_eq!(a, [[2, 1], [3, 4]]);
+    }
+    {
+        let mut a = [[1, 2], [3, 4]];
+        swap_index!(a; [0][0], [1][1]);
+        assert_eq!(a, [[4, 2], [3, 1]]);
+    }
+    {
+        let mut a = [[1, 2], [3, 4]];
+        swap_index!(a; [1][0], [0][1]);
+        assert_eq!(a, [[1, 3], [2, 4]]);
+    }
+}
+
+#[test]
+fn test_swap_index_3d() {
+    let mut a = vec![vec![vec![1, 2], vec![3]], vec![vec![4, 5, 6]]];
+    swap_index!(a; [0][0][1], [0][1][0]);
+    assert_eq!(a, vec![vec![vec![1, 3], vec![2]], vec![vec![4, 5, 6]]]);
+    swap_index!(a; [1][0][2], [0][0][0]);
+    assert_eq!(a, vec![vec![vec![6, 3], vec![2]], vec![vec![4, 5, 1]]]);
+    swap_index!(a; [1][0][0], [1][0][1]);
+    assert_eq!(a, vec![vec![vec![6, 3], vec![2]], vec![vec![5, 4, 1]]]);
+}
+
+#[test]
+fn test_swap_index_slice() {
+    let mut a = [[1, 2], [3, 4], [5, 6]];
+    let s: &mut [[i32; 2]] = &mut a[1..];
+    swap_index!(s; [0][1], [1][0]);
+    assert_eq!(a, [[1, 2], [3, 5], [4, 6]]);
+}
+
+#[test]
+fn test_swap_index_field() {
+    struct Grid {
+        cells: Vec<[i32; 2]>,
+    }
+    let mut grid = Grid {
+        cells: vec![[1, 2], [3, 4]],
+    };
+    swap_index!(grid.cells; [0][0], [1][1]);
+    assert_eq!(grid.cells, vec![[4, 2], [3, 1]]);
+}
+
+#[test]
+fn test_swap_index_no_default_no_clone() {
+    let mut a: [[Box<dyn Fn() -> i32>; 2]; 2] = [
+        [Box::new(|| 1), Box::new(|| 2)],
+        [Box::new(|| 3), Box::new(|| 4)],
+    ];
+    swap_index!(a; [0][0], [1][1]);
+    assert_eq!([a[0][0](), a[0][1](), a[1][0](), a[1][1]()], [4, 2, 3, 1]);
+}
+
+#[test]
+fn test_swap_index_eval_order() {
+    let mut log = vec![];
+    let mut a = vec![[1, 2], [3, 4]];
+    swap_index!(
+        a;
+        [{
+            log.push(100);
+            0
+        }][{
+            log.push(200);
+            1
+        }],
+        [{
+            log.push(300);
+            1
+        }][{
+            log.push(400);
+            0
+        }]
+    );
+    assert_eq!(a, vec![[1, 3], [2, 4]]);
+    assert_eq!(log, vec![100, 200, 300, 400]);
+}
+
+#[test]
+fn test_swap_index_refer_to_base() {
+    let mut v = vec![1, 2, 3, 4];
+    swap_index!(v; [0], [v.len() - 1]);
+    assert_eq!(v, vec![4, 2, 3, 1]);
+}
+
+#[test]
+#[should_panic]
+fn test_swap_index_out_of_bounds() {
+    let mut a = [[1, 2], [3, 4]];
+    swap_index!(a; [0][0], [2][0]);
+}
@@ -0,0 +1,243 @@
+//! These tests are also run under Miri in CI (`.github/workflows/miri.yml`),
+//! with both Stacked Borrows and Tree Borrows:
+//!
+//! ```text
+//! cargo +nightly miri test --test test_swap_unchecked
+//! MIRIFLAGS=-Zmiri-tree-borrows cargo +nightly miri test --test test_swap_unchecked
+//! ```
+
+use std::cell::Cell;
+
+use omniswap::{rotate_unchecked, swap_unchecked};
+
+#[test]
+fn test_swap_unchecked() {
+    {
+        let mut x = Box::new(42);
+        let mut y = Box::new(84);
+        unsafe {
+            swap_unchecked!(&mut x, &mut y);
+        }
+        assert_eq!((*x, *y), (84, 42));
+    }
+    {
+        let mut x = Box::new(42);
+        let mut y = Box::new(84);
+        unsafe {
+            swap_unchecked!(&mut x, &mut y,);
+        }
+        assert_eq!((*x, *y), (84, 42));
+    }
+}
+
+#[test]
+fn test_swap_unchecked_array() {
+    {
+        let mut a = [1, 2, 3, 4, 5].map(Box::new);
+        unsafe {
+            swap_unchecked!(&mut a[0], &mut a[2]);
+        }
+        assert_eq!(a, [3, 2, 1, 4, 5].map(Box::new));
+    }
+    {
+        let mut a = [1, 2, 3, 4, 5].map(Box::new);
+        unsafe {
+            swap_unchecked!(&mut a[0], &mut a[0]);
+        }
+        assert_eq!(a, [1, 2, 3, 4, 5].map(Box::new));
+    }
+}
+
+#[test]
+fn test_swap_unchecked_2d_array() {
+    {
+        let mut a = [[1, 2], [3, 4]].map(|row| row.map(Box::new));
+        unsafe {
+            swap_unchecked!(&mut a[0][0], &mut a[0][1]);
+        }
+        assert_eq!(a, [[2, 1], [3, 4]].map(|row| row.map(Box::new)));
+    }
+    {
+        let mut a = [[1, 2], [3, 4]].map(|row| row.map(Box::new));
+        unsafe {
+            swap_unchecked!(&mut a[0][0], &mut a[1][1]);
+        }
+        assert_eq!(a, [[4, 2], [3, 1]].map(|row| row.map(Box::new)));
+    }
+    {
+        let mut a = [[1, 2], [3, 4]].map(|row| row.map(Box::new));
+        unsafe {
+            swap_unchecked!(&mut a[1][1], &mut a[1][1]);
+        }
+        assert_eq!(a, [[1, 2], [3, 4]].map(|row| row.map(Box::new)));
+    }
+}
+
+#[test]
+fn test_swap_unchecked_vec() {
+    {
+        let mut v = vec![Box::new(1), Box::new(2), Box::new(3)];
+        unsafe {
+            swap_unchecked!(&mut v[0], &mut v[2]);
+        }
+        assert_eq!(v, vec![Box::new(3), Box::new(2), Box::new(1)]);
+    }
+    {
+        let mut v = vec![Box::new(1), Box::new(2), Box::new(3)];
+        unsafe {
+            swap_unchecked!(&mut v[1], &mut v[1]);
+        }
+        assert_eq!(v, vec![Box::new(1), Box::new(2), Box::new(3)]);
+    }
+}
+
+#[test]
+fn test_swap_unchecked_nested_vec() {
+    {
+        let mut v = vec![vec![Box::new(1), Box::new(2)], vec![Box::new(3)]];
+        unsafe {
+            swap_unchecked!(&mut v[0][1], &mut v[1][0]);
+        }
+        assert_eq!(v, vec![vec![Box::new(1), Box::new(3)], vec![Box::new(2)]]);
+    }
+    {
+        let mut v = vec![vec![Box::new(1), Box::new(2)], vec![Box::new(3)]];
+        unsafe {
+            swap_unchecked!(&mut v[0][0], &mut v[0][1]);
+        }
+        assert_eq!(v, vec![vec![Box::new(2), Box::new(1)], vec![Box::new(3)]]);
+    }
+    {
+        // Swapping whole rows
+        let mut v = vec![vec![Box::new(1), Box::new(2)], vec![Box::new(3)]];
+        unsafe {
+            swap_unchecked!(&mut v[0], &mut v[1]);
+        }
+        assert_eq!(v, vec![vec![Box::new(3)], vec![Box::new(1), Box::new(2)]]);
+    }
+}
+
+#[test]
+fn test_swap_unchecked_no_default_no_clone() {
+    let mut a: Vec<Box<dyn FnMut() -> i32>> = vec![Box::new(|| 1), Box::new(|| 2)];
+    unsafe {
+        swap_unchecked!(&mut a[0], &mut a[1]);
+    }
+    assert_eq!((a[0](), a[1]()), (2, 1));
+}
+
+#[test]
+#[allow(clippy::useless_vec)] // exercises `IndexMut` on `Vec`
+fn test_swap_unchecked_drop() {
+    let drops = Cell::new(0);
+    {
+        let mut v = vec![DropCounter(&drops), DropCounter(&drops)];
+        unsafe {
+            swap_unchecked!(&mut v[0], &mut v[1]);
+            swap_unchecked!(&mut v[0], &mut v[0]);
+            rotate_unchecked!(&mut v[1], &mut v[0], &mut v[1]);
+        }
+        assert_eq!(drops.get(), 0);
+    }
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_swap_unchecked_eval_order() {
+    let mut log = vec![];
+    let mut x = Box::new(42);
+    let mut y = Box::new(84);
+    unsafe {
+        swap_unchecked!(
+            {
+                log.push(100);
+                &mut x
+            },
+            {
+                log.push(200);
+                &mut y
+            }
+        );
+    }
+    assert_eq!((*x, *y), (84, 42));
+    assert_eq!(log, vec![100, 200, 100]);
+}
+
+#[test]
+fn test_rotate_unchecked() {
+    {
+        let mut x = Box::new(42);
+        unsafe {
+            rotate_unchecked!(&mut x);
+        }
+        assert_eq!(*x, 42);
+    }
+    {
+        let mut x = Box::new(42);
+        unsafe {
+            rotate_unchecked!(&mut x,);
+        }
+        assert_eq!(*x, 42);
+    }
+    {
+        let mut v = vec![Box::new(1), Box::new(2), Box::new(3)];
+        unsafe {
+            rotate_unchecked!(&mut v[0], &mut v[1], &mut v[2]);
+        }
+        assert_eq!(v, vec![Box::new(3), Box::new(1), Box::new(2)]);
+    }
+    {
+        let mut a = [[1, 2], [3, 4]].map(|row| row.map(Box::new));
+        unsafe {
+            rotate_unchecked!(&mut a[0][0], &mut a[1][1], &mut a[1][0], &mut a[0][1],);
+        }
+        assert_eq!(a, [[2, 3], [4, 1]].map(|row| row.map(Box::new)));
+    }
+}
+
+#[test]
+fn test_rotate_unchecked_eval_order() {
+    let mut log = vec![];
+    let mut x = Box::new(1);
+    let mut y = Box::new(2);
+    let mut z = Box::new(3);
+    unsafe {
+        rotate_unchecked!(
+            {
+                log.push(100);
+                &mut x
+            },
+            {
+                log.push(200);
+                &mut y
+            },
+            {
+                log.push(300);
+                &mut z
+            }
+        );
+    }
+    assert_eq!((*x, *y, *z), (3, 1, 2));
+    assert_eq!(log, vec![100, 200, 300, 100]);
+}
+
+struct DropCounter<'a>(&'a Cell<u32>);
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn test_swap_unchecked_panic_in_first_argument() {
+    // Nothing has been moved out yet, so the panic unwinds instead of aborting.
+    let mut a = [Box::new(1)];
+    let mut y = Box::new(2);
+    let i = std::hint::black_box(1);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+        swap_unchecked!(&mut a[i], &mut y);
+    }));
+    assert!(result.is_err());
+    assert_eq!((*a[0], *y), (1, 2));
+}
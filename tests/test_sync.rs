@@ -0,0 +1,191 @@
+#![cfg(feature = "std")]
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Mutex, RwLock};
+
+use omniswap::{
+    rotate, swap, take, try_rotate, try_swap, try_take, IgnorePoison, Replace, TryError, TryReplace,
+};
+
+fn poison_mutex<T>(m: &Mutex<T>) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let _guard = m.lock().unwrap();
+        panic!("poison");
+    }));
+    assert!(m.is_poisoned());
+}
+
+fn poison_rw_lock<T>(m: &RwLock<T>) {
+    let _ = catch_unwind(AssertUnwindSafe(|| {
+        let _guard = m.write().unwrap();
+        panic!("poison");
+    }));
+    assert!(m.is_poisoned());
+}
+
+#[test]
+fn test_take_mutex() {
+    let x = Mutex::new(vec![42]);
+    assert_eq!(take!(&x), vec![42]);
+    assert_eq!(x.into_inner().unwrap(), Vec::<i32>::new());
+
+    // Copy
+    let x = Mutex::new(42);
+    assert_eq!(take!(&x), 42);
+    assert_eq!(x.into_inner().unwrap(), 42);
+}
+
+#[test]
+fn test_take_rw_lock() {
+    let x = RwLock::new(vec![42]);
+    assert_eq!(take!(&x), vec![42]);
+    assert_eq!(x.into_inner().unwrap(), Vec::<i32>::new());
+}
+
+#[test]
+fn test_replace_mutex() {
+    let x = Mutex::new(String::from("a"));
+    assert_eq!(Replace::replace(&x, String::from("b")), "a");
+    assert_eq!(TryReplace::try_replace(&x, String::from("c")).unwrap(), "b");
+    assert_eq!(x.into_inner().unwrap(), "c");
+}
+
+#[test]
+fn test_swap_mutex() {
+    let x = Mutex::new(String::from("x"));
+    let y = RwLock::new(String::from("y"));
+    let mut z = String::from("z");
+    rotate!(&x, &y, &mut z);
+    assert_eq!(x.into_inner().unwrap(), "z");
+    assert_eq!(y.into_inner().unwrap(), "x");
+    assert_eq!(z, "y");
+}
+
+#[test]
+fn test_swap_poisoned() {
+    let x = Mutex::new(String::from("x"));
+    let y = Mutex::new(String::from("y"));
+    poison_mutex(&y);
+    let result = catch_unwind(AssertUnwindSafe(|| swap!(&x, &y)));
+    assert!(result.is_err());
+    // `x` is swapped directly with `y`, so nothing has been taken out of it.
+    assert_eq!(*x.lock().unwrap(), "x");
+    assert_eq!(*y.lock().unwrap_or_else(|e| e.into_inner()), "y");
+}
+
+#[test]
+fn test_rotate_poisoned_restore() {
+    let mut x = String::from("x");
+    let y = Mutex::new(String::from("y"));
+    let z = RwLock::new(String::from("z"));
+    poison_rw_lock(&z);
+    let result = catch_unwind(AssertUnwindSafe(|| rotate!(&mut x, &y, &z)));
+    assert!(result.is_err());
+    // The value taken out of `x` is put back, and `y` is restored.
+    assert_eq!(x, "x");
+    assert_eq!(*y.lock().unwrap(), "y");
+    assert_eq!(*z.read().unwrap_or_else(|e| e.into_inner()), "z");
+}
+
+#[test]
+fn test_try_poisoned() {
+    let x = Mutex::new(String::from("x"));
+    let y = RwLock::new(String::from("y"));
+    poison_rw_lock(&y);
+    assert!(matches!(try_take!(&y), Err(TryError::Poisoned)));
+    assert!(matches!(try_swap!(&x, &y), Err(TryError::Poisoned)));
+    assert!(matches!(
+        TryReplace::try_replace(&y, String::new()),
+        Err((TryError::Poisoned, _))
+    ));
+    assert_eq!(*x.lock().unwrap(), "x");
+    assert_eq!(*y.read().unwrap_or_else(|e| e.into_inner()), "y");
+    assert_eq!(
+        TryError::Poisoned.to_string(),
+        "poisoned lock: another task failed inside"
+    );
+}
+
+#[test]
+fn test_ignore_poison() {
+    let x = Mutex::new(String::from("x"));
+    let y = RwLock::new(String::from("y"));
+    poison_mutex(&x);
+    poison_rw_lock(&y);
+    swap!(IgnorePoison(&x), IgnorePoison(&y));
+    assert_eq!(try_take!(IgnorePoison(&x)).unwrap(), "y");
+    assert_eq!(take!(IgnorePoison(&y)), "x");
+    assert_eq!(*y.read().unwrap_or_else(|e| e.into_inner()), "");
+}
+
+#[test]
+fn test_swap_mutex_no_sentinel() {
+    let x: Mutex<Box<dyn Fn() -> i32>> = Mutex::new(Box::new(|| 1));
+    let y: RwLock<Box<dyn Fn() -> i32>> = RwLock::new(Box::new(|| 2));
+    let mut z: Box<dyn Fn() -> i32> = Box::new(|| 3);
+    swap!(&x, &y);
+    assert_eq!((x.lock().unwrap()(), y.read().unwrap()()), (2, 1));
+    swap!(&x, &mut z);
+    assert_eq!((x.lock().unwrap()(), z()), (3, 2));
+    swap!(&x, &x);
+    assert_eq!(x.lock().unwrap()(), 3);
+}
+
+#[test]
+fn test_rotate_mutex_no_sentinel() {
+    let m: Vec<Mutex<Box<dyn Fn() -> char>>> = vec![
+        Mutex::new(Box::new(|| 'a')),
+        Mutex::new(Box::new(|| 'b')),
+        Mutex::new(Box::new(|| 'c')),
+        Mutex::new(Box::new(|| 'd')),
+    ];
+    rotate!(&m[0], &m[1], &m[2], &m[3]);
+    let values = m.iter().map(|m| m.lock().unwrap()()).collect::<String>();
+    assert_eq!(values, "dabc");
+}
+
+#[test]
+fn test_rotate_mutex_restore() {
+    let x = Mutex::new(String::from("x"));
+    let y = Mutex::new(String::from("y"));
+    let z = Mutex::new(String::from("z"));
+    poison_mutex(&z);
+    assert!(matches!(try_rotate!(&x, &y, &z), Err(TryError::Poisoned)));
+    assert_eq!(*x.lock().unwrap(), "x");
+    assert_eq!(*y.lock().unwrap(), "y");
+}
+
+#[test]
+fn test_swap_mutex_no_deadlock() {
+    let x = Mutex::new(vec![1]);
+    let y = Mutex::new(vec![2]);
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            for _ in 0..10000 {
+                swap!(&x, &y);
+            }
+        });
+        s.spawn(|| {
+            for _ in 0..10000 {
+                swap!(&y, &x);
+            }
+        });
+    });
+    let mut values = [x.into_inner().unwrap(), y.into_inner().unwrap()];
+    values.sort();
+    assert_eq!(values, [vec![1], vec![2]]);
+}
+
+#[test]
+fn test_update_lock() {
+    let x = Mutex::new(vec![1, 2]);
+    omniswap::update!(&x, |mut v: Vec<i32>| {
+        v.push(3);
+        v
+    });
+    assert_eq!(*x.lock().unwrap(), [1, 2, 3]);
+
+    let x = RwLock::new(20);
+    omniswap::update!(IgnorePoison(&x), |x| x * 2 + 2);
+    assert_eq!(*x.read().unwrap(), 42);
+}
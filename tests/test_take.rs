@@ -1,6 +1,10 @@
 use std::cell::{Cell, RefCell};
 
-use omniswap::take;
+use omniswap::{take, take_or, try_take, Sentinel, TryError};
+
+mod common;
+
+use common::Handle;
 
 #[test]
 fn test_take() {
@@ -165,6 +169,188 @@ fn test_take_ref_cell_clone() {
     assert_eq!(place.value.value, vec![42]);
 }
 
+#[test]
+fn test_take_sentinel() {
+    {
+        // Clone + Copy + Default + Sentinel
+        let mut place = CloneCounter::new(WithSentinel::new(42));
+        let taken = take!(&mut place);
+        assert_eq!(taken.value.value, 42);
+        assert_eq!(taken.cloned, 0);
+        assert!(!place.value.sentinel);
+    }
+    {
+        // Clone + Default + Sentinel
+        let mut place = CloneCounter::new(WithSentinel::new(vec![42]));
+        let taken = take!(&mut place);
+        assert_eq!(taken.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        assert!(place.value.sentinel);
+    }
+    {
+        // Clone + Sentinel
+        let mut place = CloneCounter::new(NoDefault::new(WithSentinel::new(vec![42])));
+        let taken = take!(&mut place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        assert!(place.value.value.sentinel);
+    }
+    {
+        // Sentinel only
+        let mut place = NoClone::new(NoDefault::new(WithSentinel::new(vec![42])));
+        let taken = take!(&mut place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        assert!(place.value.value.sentinel);
+    }
+}
+
+#[test]
+fn test_take_cell_sentinel() {
+    {
+        // Clone + Copy + Default + Sentinel
+        let place = Cell::new(CloneCounter::new(WithSentinel::new(42)));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value, 42);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(!place.value.sentinel);
+    }
+    {
+        // Clone + Default + Sentinel
+        let place = Cell::new(CloneCounter::new(WithSentinel::new(vec![42])));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(place.value.sentinel);
+    }
+    {
+        // Clone + Sentinel
+        let place = Cell::new(CloneCounter::new(NoDefault::new(WithSentinel::new(vec![
+            42,
+        ]))));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(place.value.value.sentinel);
+    }
+    {
+        // Sentinel only
+        let place = Cell::new(NoClone::new(NoDefault::new(WithSentinel::new(vec![42]))));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        let place = place.into_inner();
+        assert!(place.value.value.sentinel);
+    }
+}
+
+#[test]
+fn test_take_ref_cell_sentinel() {
+    {
+        // Clone + Copy + Default + Sentinel
+        let place = RefCell::new(CloneCounter::new(WithSentinel::new(42)));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value, 42);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(!place.value.sentinel);
+    }
+    {
+        // Clone + Default + Sentinel
+        let place = RefCell::new(CloneCounter::new(WithSentinel::new(vec![42])));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(place.value.sentinel);
+    }
+    {
+        // Clone + Sentinel
+        let place = RefCell::new(CloneCounter::new(NoDefault::new(WithSentinel::new(vec![
+            42,
+        ]))));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        assert_eq!(taken.cloned, 0);
+        let place = place.into_inner();
+        assert!(place.value.value.sentinel);
+    }
+    {
+        // Sentinel only
+        let place = RefCell::new(NoClone::new(NoDefault::new(WithSentinel::new(vec![42]))));
+        let taken = take!(&place);
+        assert_eq!(taken.value.value.value, vec![42]);
+        let place = place.into_inner();
+        assert!(place.value.value.sentinel);
+    }
+}
+
+#[test]
+fn test_try_take() {
+    {
+        let mut place = 42;
+        let taken = try_take!(&mut place).unwrap();
+        assert_eq!(taken, 42);
+    }
+    {
+        let mut place = vec![42];
+        let taken = try_take!(&mut place,).unwrap();
+        assert_eq!(taken, vec![42]);
+        assert_eq!(place, vec![]);
+    }
+    {
+        let place = Cell::new(vec![42]);
+        let taken = try_take!(&place).unwrap();
+        assert_eq!(taken, vec![42]);
+        assert_eq!(place.into_inner(), vec![]);
+    }
+}
+
+#[test]
+fn test_try_take_ref_cell() {
+    {
+        // Copy
+        let place = RefCell::new(42);
+        let guard = place.borrow();
+        assert_eq!(try_take!(&place).unwrap(), 42);
+        drop(guard);
+        let guard = place.borrow_mut();
+        assert!(matches!(try_take!(&place), Err(TryError::Borrow(_))));
+        drop(guard);
+    }
+    {
+        // Sentinel
+        let place = RefCell::new(WithSentinel::new(vec![42]));
+        let guard = place.borrow();
+        assert!(matches!(try_take!(&place), Err(TryError::BorrowMut(_))));
+        drop(guard);
+        assert_eq!(place.borrow().value, vec![42]);
+        assert_eq!(try_take!(&place).unwrap().value, vec![42]);
+        assert!(place.borrow().sentinel);
+    }
+    {
+        // Default
+        let place = RefCell::new(vec![42]);
+        let guard = place.borrow();
+        assert!(matches!(try_take!(&place), Err(TryError::BorrowMut(_))));
+        drop(guard);
+        assert_eq!(*place.borrow(), vec![42]);
+        assert_eq!(try_take!(&place).unwrap(), vec![42]);
+        assert_eq!(*place.borrow(), vec![]);
+    }
+    {
+        // Clone
+        let place = RefCell::new(CloneCounter::new(NoDefault::new(vec![42])));
+        let guard = place.borrow();
+        assert_eq!(try_take!(&place).unwrap().cloned, 1);
+        drop(guard);
+        let guard = place.borrow_mut();
+        assert!(matches!(try_take!(&place), Err(TryError::Borrow(_))));
+        drop(guard);
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct NoDefault<T> {
     pub value: T,
@@ -176,6 +362,12 @@ impl<T> NoDefault<T> {
     }
 }
 
+impl<T: Sentinel> Sentinel for NoDefault<T> {
+    fn sentinel() -> Self {
+        Self::new(T::sentinel())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Default)]
 pub struct NoClone<T> {
     pub value: T,
@@ -187,6 +379,36 @@ impl<T> NoClone<T> {
     }
 }
 
+impl<T: Sentinel> Sentinel for NoClone<T> {
+    fn sentinel() -> Self {
+        Self::new(T::sentinel())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct WithSentinel<T> {
+    pub value: T,
+    pub sentinel: bool,
+}
+
+impl<T> WithSentinel<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            sentinel: false,
+        }
+    }
+}
+
+impl<T: Default> Sentinel for WithSentinel<T> {
+    fn sentinel() -> Self {
+        Self {
+            value: T::default(),
+            sentinel: true,
+        }
+    }
+}
+
 #[derive(Debug, Copy, Eq, Default)]
 pub struct CloneCounter<T> {
     pub value: T,
@@ -199,6 +421,12 @@ impl<T> CloneCounter<T> {
     }
 }
 
+impl<T: Sentinel> Sentinel for CloneCounter<T> {
+    fn sentinel() -> Self {
+        Self::new(T::sentinel())
+    }
+}
+
 impl<T: PartialEq> PartialEq for CloneCounter<T> {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
@@ -213,3 +441,74 @@ impl<T: Clone> Clone for CloneCounter<T> {
         }
     }
 }
+
+#[test]
+fn test_take_with_sentinel() {
+    let mut x = Handle(1);
+    assert_eq!(take!(with Handle(0); &mut x).0, 1);
+    assert_eq!(x.0, 0);
+
+    let x = Cell::new(Handle(1));
+    assert_eq!(take_or!(&x, Handle(0)).0, 1);
+    assert_eq!(x.into_inner().0, 0);
+
+    let x = RefCell::new(Handle(1));
+    assert_eq!(take_or!(&x, Handle(0),).0, 1);
+    assert_eq!(x.into_inner().0, 0);
+
+    // Even a `Copy` value is moved out.
+    let mut x = 42;
+    assert_eq!(take_or!(&mut x, 0), 42);
+    assert_eq!(x, 0);
+}
+
+#[test]
+fn test_take_with_sentinel_eval_order() {
+    // Each form evaluates its arguments in the order of appearance.
+    let mut log = vec![];
+    let mut x = String::from("x");
+    let _ = take!(
+        with {
+            log.push("sentinel");
+            String::new()
+        };
+        {
+            log.push("place");
+            &mut x
+        }
+    );
+    let _ = take_or!(
+        {
+            log.push("place");
+            &mut x
+        },
+        {
+            log.push("sentinel");
+            String::new()
+        }
+    );
+    assert_eq!(log, ["sentinel", "place", "place", "sentinel"]);
+}
+
+#[test]
+fn test_take_forced_strategy() {
+    // Copy + Default: `@default` leaves the default value instead of copying.
+    let mut x = 42;
+    assert_eq!(take!(@default &mut x), 42);
+    assert_eq!(x, 0);
+
+    // Copy + Default: `@copy` keeps the value.
+    let x = RefCell::new(42);
+    assert_eq!(take!(@copy &x), 42);
+    assert_eq!(x.into_inner(), 42);
+
+    // Default + Clone: `@clone` keeps the value.
+    let mut x = vec![1, 2];
+    assert_eq!(take!(@clone &mut x), [1, 2]);
+    assert_eq!(x, [1, 2]);
+
+    // Sentinel + Copy: `@sentinel` leaves the sentinel value.
+    let x = Cell::new(NoDefault::new(42));
+    assert_eq!(take!(@sentinel &x).value, 42);
+    assert_eq!(x.get().value, 0);
+}
@@ -0,0 +1,77 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use omniswap::transfer;
+
+#[test]
+fn test_transfer() {
+    let mut x = String::from("x");
+    let mut y = String::from("y");
+    assert_eq!(transfer!(&mut x, &mut y), "y");
+    assert_eq!((x.as_str(), y.as_str()), ("", "x"));
+
+    let x = Cell::new(vec![1]);
+    let y = RefCell::new(vec![2]);
+    assert_eq!(transfer!(&x, &y), [2]);
+    assert_eq!((x.into_inner(), y.into_inner()), (vec![], vec![1]));
+
+    let x = RefCell::new(vec![1]);
+    let mut y = vec![2];
+    assert_eq!(transfer!(&x, &mut y,), [2]);
+    assert_eq!((x.into_inner(), y), (vec![], vec![1]));
+
+    // Even `Copy` values are moved, leaving the default value behind.
+    let mut x = 1;
+    let mut y = 2;
+    assert_eq!(transfer!(&mut x, &mut y), 2);
+    assert_eq!((x, y), (0, 1));
+}
+
+#[test]
+fn test_transfer_overlapping() {
+    let mut a = vec![vec![String::from("a")], vec![String::from("b")]];
+    assert_eq!(transfer!(mut a[0][0], mut a[1][0]), "b");
+    assert_eq!(a, [[""], ["a"]]);
+
+    assert_eq!(transfer!(mut a[1][0], mut a[1][0]), "");
+    assert_eq!(a, [[""], ["a"]]);
+}
+
+#[test]
+fn test_transfer_eval_order() {
+    let mut log = Vec::new();
+    let mut a = vec![vec![1, 2], vec![3, 4]];
+    let old = transfer!(
+        mut a[{
+            log.push("i");
+            0
+        }][0],
+        {
+            log.push("to");
+            &mut a[1][1]
+        }
+    );
+    assert_eq!(old, 4);
+    assert_eq!(a, [[0, 2], [3, 1]]);
+    assert_eq!(log, ["i", "to"]);
+}
+
+#[test]
+fn test_transfer_restore() {
+    // The payload is put back if the destination is borrowed.
+    let x = RefCell::new(String::from("payload"));
+    let y = RefCell::new(String::from("old"));
+    let guard = y.borrow();
+    let result = catch_unwind(AssertUnwindSafe(|| transfer!(&x, &y)));
+    assert!(result.is_err());
+    drop(guard);
+    assert_eq!(x.into_inner(), "payload");
+    assert_eq!(y.into_inner(), "old");
+
+    // If evaluating the destination panics, the payload is dropped instead.
+    let mut x = String::from("payload");
+    let mut v = Vec::<String>::new();
+    let result = catch_unwind(AssertUnwindSafe(|| transfer!(&mut x, &mut v[0])));
+    assert!(result.is_err());
+    assert_eq!(x, "");
+}
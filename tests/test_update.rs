@@ -0,0 +1,117 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process::Command;
+
+use omniswap::update;
+
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+enum State {
+    #[default]
+    Idle,
+    Running(Vec<u32>),
+    Done(usize),
+}
+
+fn finish(state: State) -> State {
+    match state {
+        State::Running(jobs) => State::Done(jobs.len()),
+        state => state,
+    }
+}
+
+#[test]
+fn test_update() {
+    let mut x = State::Running(vec![1, 2]);
+    update!(&mut x, finish);
+    assert_eq!(x, State::Done(2));
+
+    let x = Cell::new(State::Running(vec![1, 2]));
+    update!(&x, finish);
+    assert_eq!(x.into_inner(), State::Done(2));
+
+    let x = RefCell::new(State::Running(vec![1, 2]));
+    update!(&x, finish,);
+    assert_eq!(x.into_inner(), State::Done(2));
+
+    let mut x = 20;
+    update!(&mut x, |x| x * 2 + 2);
+    assert_eq!(x, 42);
+}
+
+#[test]
+fn test_update_eval_order() {
+    let mut log = Vec::new();
+    let mut x = 1;
+    update!(
+        {
+            log.push("place");
+            &mut x
+        },
+        {
+            log.push("closure");
+            |x| x + 1
+        }
+    );
+    assert_eq!(x, 2);
+    assert_eq!(log, ["place", "closure"]);
+}
+
+#[test]
+fn test_update_panic_leave() {
+    let x = RefCell::new(State::Running(vec![1, 2]));
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        update!(&x, |_: State| -> State { panic!("failed") });
+    }));
+    assert!(result.is_err());
+    assert_eq!(x.into_inner(), State::Idle);
+}
+
+#[test]
+fn test_update_panic_restore() {
+    let mut x = State::Running(vec![1, 2]);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        update!(@restore &mut x, |_: State| -> State { panic!("failed") });
+    }));
+    assert!(result.is_err());
+    assert_eq!(x, State::Running(vec![1, 2]));
+
+    let x = RefCell::new(State::Running(vec![1, 2]));
+    update!(@restore &x, finish);
+    assert_eq!(x.into_inner(), State::Done(2));
+}
+
+#[test]
+fn test_update_abort_no_panic() {
+    // The abort policy only matters if the closure panics.
+    let mut x = State::Running(vec![1, 2]);
+    update!(@abort &mut x, finish);
+    assert_eq!(x, State::Done(2));
+}
+
+#[test]
+fn test_update_abort() {
+    // The abort cannot be caught, so the test runs itself again in a child process.
+    const CHILD: &str = "OMNISWAP_TEST_UPDATE_ABORT";
+    if std::env::var_os(CHILD).is_some() {
+        let mut x = State::Running(vec![1, 2]);
+        update!(@abort &mut x, |_| -> State { panic!("closure") });
+        return;
+    }
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["test_update_abort", "--exact", "--nocapture"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(output.status.signal(), Some(6), "not aborted: {:?}", output);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("the closure passed to update! panicked"),
+        "{}",
+        stderr
+    );
+}
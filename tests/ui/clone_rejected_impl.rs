@@ -0,0 +1,9 @@
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+impl omniswap::CloneRejected for Mesh {}
+
+fn main() {
+    let mut x = Mesh(vec![1.0]);
+    let _ = omniswap::take_no_clone!(&mut x);
+}
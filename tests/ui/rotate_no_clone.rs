@@ -0,0 +1,7 @@
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+fn main() {
+    let mut a = [Mesh(vec![1.0]), Mesh(vec![2.0]), Mesh(vec![3.0])];
+    omniswap::rotate_no_clone!(mut a[0], mut a[1], mut a[2]);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    let mut a = [3, 2, 1];
+    omniswap::sort2!(mut a[0], mut a[1], mut a[2]);
+}
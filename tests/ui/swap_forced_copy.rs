@@ -0,0 +1,5 @@
+fn main() {
+    let mut x = vec![1];
+    let mut y = vec![2];
+    omniswap::swap!(@copy &mut x, &mut y);
+}
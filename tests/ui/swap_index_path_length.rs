@@ -0,0 +1,4 @@
+fn main() {
+    let mut a = [[1, 2], [3, 4]];
+    omniswap::swap_index!(a; [0][0], [1]);
+}
@@ -0,0 +1,12 @@
+use std::cell::RefCell;
+
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+fn main() {
+    let x = RefCell::new(Mesh(vec![1.0]));
+    let mut y = Mesh(vec![2.0]);
+    // Cells are swapped directly; only `&mut Mesh` as the first place is rejected.
+    omniswap::swap_no_clone!(&x, &mut y);
+    omniswap::swap_no_clone!(&mut y, &x);
+}
@@ -0,0 +1,6 @@
+struct Handle(u32);
+
+fn main() {
+    let mut a = [Handle(1), Handle(2)];
+    omniswap::swap!(mut a[0], mut a[1]);
+}
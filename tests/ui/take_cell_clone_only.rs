@@ -0,0 +1,9 @@
+use std::cell::Cell;
+
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+fn main() {
+    let x = Cell::new(Mesh(vec![1.0]));
+    let _ = omniswap::take!(&x);
+}
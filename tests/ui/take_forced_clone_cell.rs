@@ -0,0 +1,6 @@
+use std::cell::Cell;
+
+fn main() {
+    let x = Cell::new(vec![1]);
+    let _ = omniswap::take!(@clone &x);
+}
@@ -0,0 +1,6 @@
+struct Handle(u32);
+
+fn main() {
+    let mut x = Handle(1);
+    let _ = omniswap::take!(&mut x);
+}
@@ -0,0 +1,7 @@
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+fn main() {
+    let mut x = Mesh(vec![1.0]);
+    let _ = omniswap::take_no_clone!(&mut x);
+}
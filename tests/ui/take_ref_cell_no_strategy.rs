@@ -0,0 +1,8 @@
+use std::cell::RefCell;
+
+struct Handle(u32);
+
+fn main() {
+    let x = RefCell::new(Handle(1));
+    let _ = omniswap::try_take!(&x);
+}
@@ -0,0 +1,8 @@
+struct Handle(u32);
+
+impl omniswap::Takeable for Handle {}
+
+fn main() {
+    let mut x = Handle(1);
+    let _ = omniswap::take!(&mut x);
+}
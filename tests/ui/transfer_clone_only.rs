@@ -0,0 +1,8 @@
+#[derive(Clone)]
+struct Mesh(Vec<f32>);
+
+fn main() {
+    let mut x = Mesh(vec![1.0]);
+    let mut y = Mesh(vec![2.0]);
+    let _ = omniswap::transfer!(&mut x, &mut y);
+}